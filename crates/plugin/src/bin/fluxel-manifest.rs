@@ -0,0 +1,57 @@
+//! Writes the Custom Elements Manifest of a source tree.
+//!
+//! ```sh
+//! fluxel-manifest [--config <plugin config.json>] [--out <custom-elements.json>] [<dir>]
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use swc_plugin_fluxel::Config;
+use swc_plugin_fluxel::manifest::generate;
+
+const USAGE: &str =
+    "usage: fluxel-manifest [--config <plugin config.json>] [--out <custom-elements.json>] [<dir>]";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut root = PathBuf::from(".");
+    let mut out = PathBuf::from("custom-elements.json");
+    let mut config = Config::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{arg} takes a value\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "-c" | "--config" => {
+                let path = value()?;
+                let json =
+                    std::fs::read_to_string(&path).map_err(|err| format!("{path}: {err}"))?;
+                config = Config::from_json(&json).map_err(|err| format!("{path}: {err}"))?;
+            }
+            "-o" | "--out" => out = value()?.into(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option {arg}\n{USAGE}")),
+            _ => root = arg.into(),
+        }
+    }
+
+    let manifest = generate(&root, &config).map_err(|err| err.to_string())?;
+    let json = serde_json::to_string_pretty(&manifest).map_err(|err| err.to_string())?;
+    std::fs::write(&out, json + "\n").map_err(|err| format!("{}: {err}", out.display()))
+}
@@ -0,0 +1,149 @@
+//! Static description of a component lifted out of a module.
+
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::Ident;
+use swc_core::ecma::atoms::Atom;
+use swc_core::ecma::utils::private_ident;
+
+use crate::emit::ComponentEvent;
+use crate::options::ShadowRoot;
+use crate::props::ComponentProp;
+use crate::utils::kebab_case;
+
+/// A function that gets lifted into a Custom Element.
+pub(crate) struct Component {
+    /// Identifier of the component function, e.g. `Counter`.
+    pub fn_ident: Ident,
+    /// Identifier of the generated element class, e.g. `CounterElement`.
+    pub class_ident: Ident,
+    /// Custom element name passed to `customElements.define`, e.g. `fluxel-counter`.
+    pub tag_name: String,
+    /// Props taken by the component, each backed by a signal and an attribute.
+    pub props: Vec<ComponentProp>,
+    /// How the module exported the component; the element class takes its place.
+    pub export: Export,
+    /// Events dispatched by the shadow root, see [`crate::Config::delegate_events`].
+    pub delegated_events: Vec<String>,
+    /// Custom events dispatched on the element, see [`crate::emit`].
+    pub events: Vec<ComponentEvent>,
+    /// Names of the slots rendered by the component, `""` for the default
+    /// slot; see [`crate::slots`].
+    pub slots: Vec<Atom>,
+    /// Module-level style sheets (or style texts with
+    /// [`crate::Config::style_tags`]) applied to the shadow root.
+    pub styles: Vec<Ident>,
+    /// Whether the element renders through a lifecycle, see [`crate::lifecycle`].
+    pub lifecycle: bool,
+    /// Whether the element takes part in forms, see [`crate::lifecycle`].
+    pub form_associated: bool,
+    /// Shadow root attached by the element, `None` rendering into the light
+    /// DOM; see [`crate::options`].
+    pub shadow: Option<ShadowRoot>,
+    /// Start of the declaration the JSDoc of the component is attached to.
+    pub doc: BytePos,
+}
+
+/// Export through which a component was declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Export {
+    /// `export default function Counter() {}`
+    Default,
+    /// `export function Counter() {}`, re-exported as `export { CounterElement as Counter }`.
+    Named,
+    /// Marked but not exported.
+    None,
+}
+
+impl Component {
+    /// `tag_name` overrides the name derived from `tag_prefix` and the function.
+    pub fn new(
+        fn_ident: Ident,
+        export: Export,
+        tag_name: Option<String>,
+        tag_prefix: &str,
+        doc: BytePos,
+    ) -> Self {
+        let class_ident = private_ident!(format!("{}Element", fn_ident.sym));
+        let tag_name = tag_name.unwrap_or_else(|| self::tag_name(tag_prefix, &fn_ident.sym));
+
+        Self {
+            fn_ident,
+            class_ident,
+            tag_name,
+            props: vec![],
+            export,
+            delegated_events: vec![],
+            events: vec![],
+            slots: vec![],
+            styles: vec![],
+            lifecycle: false,
+            form_associated: false,
+            shadow: Some(ShadowRoot::default()),
+            doc,
+        }
+    }
+}
+
+/// Builds a kebab-case tag name `<prefix>-<component>`.
+fn tag_name(prefix: &str, name: &str) -> String {
+    format!("{prefix}-{}", kebab_case(name))
+}
+
+/// Names the HTML spec reserves for SVG and MathML elements.
+const RESERVED_NAMES: &[&str] = &[
+    "annotation-xml",
+    "color-profile",
+    "font-face",
+    "font-face-src",
+    "font-face-uri",
+    "font-face-format",
+    "font-face-name",
+    "missing-glyph",
+];
+
+/// Checks `name` against the spec's [valid custom element name] rules, which
+/// `customElements.define` enforces by throwing.
+///
+/// [valid custom element name]: https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name
+pub(crate) fn validate_tag_name(name: &str) -> Result<(), String> {
+    if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err(format!(
+            "`{name}` is not a valid custom element name: it must start with a lowercase ASCII letter"
+        ));
+    }
+    if !name.contains('-') {
+        return Err(format!(
+            "`{name}` is not a valid custom element name: it must contain a hyphen"
+        ));
+    }
+    if let Some(c) = name.chars().find(|&c| !is_pcen_char(c)) {
+        return Err(format!(
+            "`{name}` is not a valid custom element name: `{c}` is not allowed"
+        ));
+    }
+    if RESERVED_NAMES.contains(&name) {
+        return Err(format!(
+            "`{name}` is reserved by SVG and MathML and cannot name a custom element"
+        ));
+    }
+    Ok(())
+}
+
+/// `PCENChar` production of the spec.
+fn is_pcen_char(c: char) -> bool {
+    matches!(c,
+        '-' | '.' | '0'..='9' | '_' | 'a'..='z' | '\u{B7}'
+        | '\u{C0}'..='\u{D6}'
+        | '\u{D8}'..='\u{F6}'
+        | '\u{F8}'..='\u{37D}'
+        | '\u{37F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{203F}'..='\u{2040}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}'
+    )
+}
@@ -0,0 +1,93 @@
+//! Plugin options.
+//!
+//! Read from the JSON the host passes next to the plugin, e.g. in `.swcrc`:
+//!
+//! ```json
+//! ["swc-plugin-fluxel", { "tagPrefix": "acme", "shadowMode": "closed" }]
+//! ```
+
+use serde::Deserialize;
+
+/// Options controlling the generated code.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct Config {
+    /// Prefix of the generated custom element names: `<prefix>-<component>`.
+    pub tag_prefix: String,
+    /// Mode of the shadow root attached by every element, unless its module
+    /// or component sets the `shadow` option.
+    pub shadow_mode: ShadowMode,
+    /// How JSX is turned into DOM nodes.
+    pub codegen: Codegen,
+    /// Development build: elements tolerate their module being evaluated more
+    /// than once, as happens on hot reload.
+    pub dev: bool,
+    /// Module the runtime helpers (`signal`, `effect`, ...) are imported from.
+    pub jsx_import_source: String,
+    /// Props reflected to their attribute in every component, in addition to
+    /// the ones tagged `@reflect` in the props type.
+    pub reflect: Vec<String>,
+    /// Handle common bubbling events (`click`, `input`, `keydown`, ...) with
+    /// one listener per shadow root instead of one per element.
+    pub delegate_events: bool,
+    /// Style shadow roots with `<style>` elements instead of adopted
+    /// Constructable StyleSheets, for browsers that lack the latter.
+    pub style_tags: bool,
+    /// Call the component on the first `connectedCallback` instead of in the
+    /// constructor, which the custom element spec expects to leave attributes
+    /// and children alone. The constructor only attaches the shadow root.
+    pub defer_render: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tag_prefix: "fluxel".into(),
+            shadow_mode: ShadowMode::default(),
+            codegen: Codegen::default(),
+            dev: false,
+            jsx_import_source: "@fluxel/core".into(),
+            reflect: vec![],
+            delegate_events: false,
+            style_tags: false,
+            defer_render: true,
+        }
+    }
+}
+
+impl Config {
+    /// Parses the plugin options, rejecting unknown keys and mistyped values.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Strategy used to construct the DOM of a component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codegen {
+    /// One `document.createElement` call per element.
+    #[default]
+    Dom,
+    /// Static markup is parsed once into a module-level `<template>` and
+    /// cloned per instance; only the dynamic holes are touched afterwards.
+    Template,
+}
+
+/// `mode` passed to `attachShadow`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShadowMode {
+    #[default]
+    Open,
+    Closed,
+}
+
+impl ShadowMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ShadowMode::Open => "open",
+            ShadowMode::Closed => "closed",
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Build-time checks and minification of component styles.
+//!
+//! Styles are parsed with lightningcss. Syntax errors fail the build, and
+//! selectors that cannot match anything inside a shadow root (`html`, `body`,
+//! `:root`) are reported as warnings, since the element's styles only ever see
+//! its own shadow tree:
+//!
+//! ```css
+//! :root { --accent: tomato; } /* never applies, use `:host` */
+//! ```
+
+use lightningcss::rules::{CssRule, CssRuleList, Location};
+use lightningcss::selector::{Component, SelectorList};
+use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
+
+/// A message about the style text, at a byte offset into it.
+#[derive(Debug)]
+pub(crate) struct Diagnostic {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Result of [`check`] for valid styles.
+#[derive(Debug, Default)]
+pub(crate) struct Checked {
+    /// Minified text, when requested.
+    pub minified: Option<String>,
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// Validates `css`, optionally minifying it. Errors are syntax errors.
+pub(crate) fn check(css: &str, minify: bool) -> Result<Checked, Diagnostic> {
+    let mut sheet = StyleSheet::parse(css, ParserOptions::default()).map_err(|err| {
+        let offset = err
+            .loc
+            .as_ref()
+            .map_or(0, |loc| offset(css, loc.line, loc.column));
+        Diagnostic {
+            offset,
+            message: format!("invalid CSS: {}", err.kind),
+        }
+    })?;
+
+    let mut checked = Checked::default();
+    unreachable_selectors(css, &sheet.rules, &mut checked.warnings);
+
+    if minify {
+        // neither step fails for a stylesheet that parsed
+        let minified = sheet.minify(MinifyOptions::default()).ok().and_then(|()| {
+            sheet
+                .to_css(PrinterOptions {
+                    minify: true,
+                    ..Default::default()
+                })
+                .ok()
+        });
+        checked.minified = minified.map(|out| out.code);
+    }
+
+    Ok(checked)
+}
+
+fn unreachable_selectors(css: &str, rules: &CssRuleList, warnings: &mut Vec<Diagnostic>) {
+    for rule in &rules.0 {
+        match rule {
+            CssRule::Style(style) => {
+                check_selectors(css, &style.selectors, style.loc, warnings);
+                unreachable_selectors(css, &style.rules, warnings);
+            }
+            CssRule::Nesting(nesting) => {
+                let style = &nesting.style;
+                check_selectors(css, &style.selectors, style.loc, warnings);
+                unreachable_selectors(css, &style.rules, warnings);
+            }
+            CssRule::Media(media) => unreachable_selectors(css, &media.rules, warnings),
+            CssRule::Supports(supports) => unreachable_selectors(css, &supports.rules, warnings),
+            CssRule::LayerBlock(layer) => unreachable_selectors(css, &layer.rules, warnings),
+            CssRule::Container(container) => unreachable_selectors(css, &container.rules, warnings),
+            CssRule::Scope(scope) => unreachable_selectors(css, &scope.rules, warnings),
+            CssRule::StartingStyle(starting) => {
+                unreachable_selectors(css, &starting.rules, warnings)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_selectors(
+    css: &str,
+    selectors: &SelectorList,
+    loc: Location,
+    warnings: &mut Vec<Diagnostic>,
+) {
+    for selector in &selectors.0 {
+        let outside = selector
+            .iter_raw_match_order()
+            .find_map(|component| match component {
+                Component::LocalName(name) => {
+                    let name: &str = name.lower_name.as_ref();
+                    matches!(name, "html" | "body").then(|| name.to_string())
+                }
+                Component::Root => Some(":root".into()),
+                _ => None,
+            });
+        if let Some(outside) = outside {
+            warnings.push(Diagnostic {
+                offset: offset(css, loc.line, loc.column),
+                message: format!(
+                    "`{outside}` never matches inside a shadow root; style the element with `:host`"
+                ),
+            });
+        }
+    }
+}
+
+/// Byte offset of a 0-based line and a 1-based column counted in UTF-16
+/// code units, as lightningcss reports them.
+fn offset(css: &str, line: u32, column: u32) -> usize {
+    let start: usize = css
+        .split_inclusive('\n')
+        .take(line as usize)
+        .map(str::len)
+        .sum();
+    let mut units = 1;
+    for (idx, c) in css[start..].char_indices() {
+        if units >= column as usize || c == '\n' {
+            return start + idx;
+        }
+        units += c.len_utf16();
+    }
+    css.len()
+}
@@ -0,0 +1,471 @@
+//! Finds the components of a module and normalizes each into a plain
+//! top-level `function` declaration.
+//!
+//! A module declares components through
+//!
+//! - its default export: `export default function Counter() {}`, or a
+//!   reference to a top-level function, arrow or `memo(...)` bound to a
+//!   `const`; anonymous ones are named after the file. Only functions
+//!   rendering JSX, or marked `@customElement`, are components, so utility
+//!   modules and story files pass through untouched;
+//! - a JSDoc marker: `/** @customElement acme-icon */ export function Icon() {}`;
+//! - a wrapper call: `export const Icon = defineElement((props) => ...)`, with
+//!   an optional tag name as first argument.
+
+use std::path::Path;
+
+use swc_core::common::comments::Comments;
+use swc_core::common::util::take::Take;
+use swc_core::common::{BytePos, Span, Spanned};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::{ExprFactory, private_ident};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::component::{Component, Export};
+use crate::imports::RuntimeImports;
+use crate::jsdoc;
+use crate::utils::{block, emit_error, pascal_case};
+
+/// Runtime marker wrapping a component declared through a variable.
+const DEFINE_ELEMENT: &str = "defineElement";
+
+/// Calls looked through when resolving a component: `memo(() => ...)`.
+const WRAPPERS: &[&str] = &["memo"];
+
+/// Collects the components of `m`, rewriting their declarations into plain
+/// functions. Default exports by reference (`export default Counter;`) are
+/// left for the caller to replace with the element class.
+pub(crate) fn find_components(
+    m: &mut Module,
+    comments: &dyn Comments,
+    imports: &RuntimeImports,
+    filename: Option<&str>,
+    tag_prefix: &str,
+) -> Vec<Component> {
+    // modules without JSX are not expected to export components
+    let diagnose = renders_jsx(&*m);
+    let filename_ident = filename
+        .and_then(component_name)
+        .map(|name| private_ident!(name));
+    let marked = default_export_marked(m, comments);
+    name_default_export(m, filename_ident, diagnose, marked);
+    inline_default_const(m, comments, marked);
+
+    let define_element = imports.find(DEFINE_ELEMENT).map(Ident::to_id);
+    let mut components = vec![];
+
+    for item in &mut m.body {
+        // JSDoc of the component
+        let doc = item.span_lo();
+        // the plain declaration replacing `item`, if it has to change
+        let found = match item {
+            // export default function Counter() {}
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(_)) => {
+                let tag = jsdoc::tag(comments, doc, "customElement");
+                take_default_fn_decl(item, tag.is_some())
+                    .map(|fn_decl| (Some(fn_decl), Export::Default, tag))
+            }
+            // /** @customElement */ export function Icon() {}
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Fn(fn_decl),
+                ..
+            })) => jsdoc::tag(comments, doc, "customElement")
+                .map(|tag| (Some(fn_decl.take()), Export::Named, Some(tag))),
+            // /** @customElement */ function Icon() {}
+            ModuleItem::Stmt(Stmt::Decl(Decl::Fn(_))) => jsdoc::tag(comments, doc, "customElement")
+                .map(|tag| (None, Export::None, Some(tag))),
+            // export const Icon = defineElement(...);
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var),
+                ..
+            })) => define_element
+                .as_ref()
+                .and_then(|callee| take_define_element(var, callee))
+                .map(|(fn_decl, tag)| (Some(fn_decl), Export::Named, tag)),
+            // const Icon = defineElement(...);
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => define_element
+                .as_ref()
+                .and_then(|callee| take_define_element(var, callee))
+                .map(|(fn_decl, tag)| (Some(fn_decl), Export::None, tag)),
+            _ => None,
+        };
+
+        let Some((fn_decl, export, tag)) = found else {
+            continue;
+        };
+        if let Some(fn_decl) = fn_decl {
+            *item = ModuleItem::Stmt(fn_decl.into());
+        }
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Fn(FnDecl { ident, .. }))) = item else {
+            unreachable!("components are normalized into function declarations");
+        };
+        // an empty `@customElement` derives the name like any other component
+        let tag = tag.filter(|tag| !tag.is_empty());
+        components.push(Component::new(ident.clone(), export, tag, tag_prefix, doc));
+    }
+
+    // function Counter() {}
+    // export default Counter;
+    match default_ref(m) {
+        // /** @customElement */ function Counter() {}
+        Some(ident)
+            if let Some(component) = components
+                .iter_mut()
+                .find(|component| component.fn_ident.to_id() == ident.to_id()) =>
+        {
+            component.export = Export::Default;
+        }
+        Some(ident)
+            if let Some((doc, renders_jsx)) = fn_decl(m, &ident)
+                && (renders_jsx
+                    || marked
+                    || jsdoc::tag(comments, doc, "customElement").is_some()) =>
+        {
+            components.push(Component::new(
+                ident,
+                Export::Default,
+                None,
+                tag_prefix,
+                doc,
+            ));
+        }
+        Some(ident) if marked && declares_value(m, &ident) => emit_error(
+            ident.span,
+            &format!(
+                "`{}` is default-exported but is not a function component",
+                ident.sym
+            ),
+        ),
+        _ => {}
+    }
+
+    components
+}
+
+/// PascalCase component name derived from the basename of `filename`, up to
+/// its first dot: `src/date-picker.stories.tsx` -> `DatePicker`.
+fn component_name(filename: &str) -> Option<String> {
+    let basename = Path::new(filename).file_name()?.to_str()?;
+    let stem = basename.split('.').next()?;
+    let name = pascal_case(stem);
+    name.starts_with(|c: char| c.is_alphabetic())
+        .then_some(name)
+}
+
+/// Whether the default export of `m` is marked `@customElement`.
+fn default_export_marked(m: &Module, comments: &dyn Comments) -> bool {
+    m.body.iter().any(|item| {
+        matches!(
+            item,
+            ModuleItem::ModuleDecl(
+                ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_)
+            )
+        ) && jsdoc::tag(comments, item.span_lo(), "customElement").is_some()
+    })
+}
+
+/// Turns a default-exported function expression, arrow or `memo(...)` into
+/// `export default function <name>() {}` when it is a component, i.e.
+/// renders JSX or is `marked`. Anonymous functions are named after the file;
+/// without a filename they are reported.
+fn name_default_export(
+    m: &mut Module,
+    filename_ident: Option<Ident>,
+    diagnose: bool,
+    marked: bool,
+) {
+    for item in &mut m.body {
+        let ModuleItem::ModuleDecl(decl) = item else {
+            continue;
+        };
+
+        match decl {
+            // export default function () {}
+            ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                decl: DefaultDecl::Fn(fn_expr),
+                ..
+            }) if fn_expr.ident.is_none() && (marked || renders_jsx(&fn_expr.function)) => {
+                match &filename_ident {
+                    // diagnostics about the component point at the function
+                    Some(name) => {
+                        fn_expr.ident = Some(Ident {
+                            span: fn_expr.function.span,
+                            ..name.clone()
+                        })
+                    }
+                    None if diagnose => unnamed(fn_expr.function.span),
+                    None => {}
+                }
+            }
+            // export default () => ...
+            // export default memo(function Counter() {})
+            ModuleDecl::ExportDefaultExpr(ExportDefaultExpr { span, expr }) => {
+                let Some(component) = component_fn(expr) else {
+                    // e.g. the `{ title, component }` of a story file
+                    if marked && !expr.is_ident() {
+                        emit_error(expr.span(), "default export is not a function component");
+                    }
+                    return;
+                };
+                if !marked && !renders_jsx(&*component) {
+                    return;
+                }
+
+                let ident = match (&*component, &filename_ident) {
+                    (
+                        Expr::Fn(FnExpr {
+                            ident: Some(ident), ..
+                        }),
+                        _,
+                    ) => ident.clone(),
+                    (_, Some(name)) => Ident {
+                        span: component.span(),
+                        ..name.clone()
+                    },
+                    (_, None) => {
+                        if diagnose {
+                            unnamed(component.span());
+                        }
+                        return;
+                    }
+                };
+                *decl = ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                    span: *span,
+                    decl: DefaultDecl::Fn(FnExpr {
+                        ident: Some(ident),
+                        function: Box::new(take_fn(component)),
+                    }),
+                });
+            }
+            _ => continue,
+        }
+        return;
+    }
+}
+
+fn unnamed(span: Span) {
+    emit_error(
+        span,
+        "anonymous components need a name: name the function or let the host pass the filename",
+    );
+}
+
+/// Turns `const Counter = (props) => ...;` into `function Counter(props) {}`
+/// when the module ends in `export default Counter;` and the function is a
+/// component: it renders JSX, or either declaration is `marked`.
+fn inline_default_const(m: &mut Module, comments: &dyn Comments, marked: bool) {
+    let Some(target) = default_ref(m) else {
+        return;
+    };
+
+    for item in &mut m.body {
+        let marked = marked || jsdoc::tag(comments, item.span_lo(), "customElement").is_some();
+        let decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &mut export.decl,
+            _ => continue,
+        };
+        let Decl::Var(var) = decl else {
+            continue;
+        };
+        // reassignable bindings may not hold the function by the time the element renders
+        if var.kind != VarDeclKind::Const {
+            continue;
+        }
+        let [declarator] = &mut var.decls[..] else {
+            continue;
+        };
+        let Pat::Ident(BindingIdent { id, .. }) = &declarator.name else {
+            continue;
+        };
+        if id.to_id() != target.to_id() {
+            continue;
+        }
+        let Some(component) = declarator.init.as_deref_mut().and_then(component_fn) else {
+            return;
+        };
+        if !marked && !renders_jsx(&*component) {
+            return;
+        }
+
+        *decl = Decl::Fn(FnDecl {
+            ident: id.clone(),
+            declare: false,
+            function: Box::new(take_fn(component)),
+        });
+        return;
+    }
+}
+
+/// The arrow or function expression `expr` evaluates to, looking through
+/// parentheses, type assertions and [`WRAPPERS`].
+fn component_fn(expr: &mut Expr) -> Option<&mut Expr> {
+    match expr {
+        Expr::Arrow(_) | Expr::Fn(_) => Some(expr),
+        Expr::Paren(ParenExpr { expr, .. })
+        | Expr::TsAs(TsAsExpr { expr, .. })
+        | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. })
+        | Expr::TsConstAssertion(TsConstAssertion { expr, .. }) => component_fn(expr),
+        Expr::Call(CallExpr { callee, args, .. })
+            if callee
+                .as_expr()
+                .and_then(|callee| callee.as_ident())
+                .is_some_and(|callee| WRAPPERS.contains(&&*callee.sym)) =>
+        {
+            component_fn(&mut args.first_mut()?.expr)
+        }
+        _ => None,
+    }
+}
+
+/// Takes the function out of an expression found by [`component_fn`].
+fn take_fn(expr: &mut Expr) -> Function {
+    match expr.take() {
+        Expr::Arrow(arrow) => arrow_to_function(arrow),
+        Expr::Fn(fn_expr) => *fn_expr.function,
+        _ => unreachable!("component_fn only yields functions"),
+    }
+}
+
+/// `(a) => expr` -> `function (a) { return expr; }`
+fn arrow_to_function(arrow: ArrowExpr) -> Function {
+    let body = match *arrow.body {
+        BlockStmtOrExpr::BlockStmt(body) => body,
+        BlockStmtOrExpr::Expr(expr) => block(vec![expr.into_return_stmt().into()]),
+    };
+
+    Function {
+        params: arrow.params.into_iter().map(Param::from).collect(),
+        body: Some(body),
+        is_async: arrow.is_async,
+        type_params: arrow.type_params,
+        return_type: arrow.return_type,
+        span: arrow.span,
+        ctxt: arrow.ctxt,
+        ..Default::default()
+    }
+}
+
+/// `const Icon = defineElement("acme-icon", (props) => ...)` ->
+/// `function Icon(props) { ... }` and the explicit tag name, if any.
+fn take_define_element(var: &mut VarDecl, callee: &Id) -> Option<(FnDecl, Option<String>)> {
+    let [declarator] = &mut var.decls[..] else {
+        return None;
+    };
+    let Pat::Ident(BindingIdent { id, .. }) = &declarator.name else {
+        return None;
+    };
+    let Some(Expr::Call(call)) = declarator.init.as_deref_mut() else {
+        return None;
+    };
+    let is_marker = call
+        .callee
+        .as_expr()
+        .and_then(|callee| callee.as_ident())
+        .is_some_and(|ident| ident.to_id() == *callee);
+    if !is_marker {
+        return None;
+    }
+
+    let (tag, component) = match &mut call.args[..] {
+        [component] => (None, component),
+        [tag, component] => match &*tag.expr {
+            Expr::Lit(Lit::Str(tag)) => (Some(tag.value.to_string()), component),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let function = take_fn(component_fn(&mut component.expr)?);
+
+    let fn_decl = FnDecl {
+        ident: id.clone(),
+        declare: false,
+        function: Box::new(function),
+    };
+    Some((fn_decl, tag))
+}
+
+/// The identifier of `export default Counter;`.
+fn default_ref(m: &Module) -> Option<Ident> {
+    m.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+            expr, ..
+        })) => expr.as_ident().cloned(),
+        _ => None,
+    })
+}
+
+/// Start of the top-level function declaration binding `ident`, and whether
+/// the function renders JSX.
+fn fn_decl(m: &Module, ident: &Ident) -> Option<(BytePos, bool)> {
+    m.body.iter().find_map(|item| {
+        let decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            _ => return None,
+        };
+        match decl {
+            Decl::Fn(f) if f.ident.to_id() == ident.to_id() => {
+                Some((item.span_lo(), renders_jsx(&*f.function)))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Whether `ident` is bound by a top-level variable or class declaration.
+fn declares_value(m: &Module, ident: &Ident) -> bool {
+    m.body.iter().any(|item| {
+        let decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            _ => return false,
+        };
+        match decl {
+            Decl::Var(var) => var.decls.iter().any(|declarator| {
+                matches!(&declarator.name, Pat::Ident(id) if id.to_id() == ident.to_id())
+            }),
+            Decl::Class(class) => class.ident.to_id() == ident.to_id(),
+            _ => false,
+        }
+    })
+}
+
+/// Whether `node` contains JSX, which tells components from other functions.
+fn renders_jsx<N: VisitWith<FindJsx>>(node: &N) -> bool {
+    let mut finder = FindJsx(false);
+    node.visit_with(&mut finder);
+    finder.0
+}
+
+struct FindJsx(bool);
+
+impl Visit for FindJsx {
+    fn visit_jsx_element(&mut self, _: &JSXElement) {
+        self.0 = true;
+    }
+
+    fn visit_jsx_fragment(&mut self, _: &JSXFragment) {
+        self.0 = true;
+    }
+}
+
+/// Turns `export default function Name() {}` into `function Name() {}` when
+/// it renders JSX or is `marked`.
+fn take_default_fn_decl(item: &mut ModuleItem, marked: bool) -> Option<FnDecl> {
+    let ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) = item else {
+        return None;
+    };
+    let DefaultDecl::Fn(fn_expr) = &mut export.decl else {
+        return None;
+    };
+    if !marked && !renders_jsx(&*fn_expr.function) {
+        return None;
+    }
+
+    Some(FnDecl {
+        ident: fn_expr.ident.take()?,
+        declare: false,
+        function: fn_expr.function.take(),
+    })
+}
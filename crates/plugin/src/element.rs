@@ -0,0 +1,820 @@
+//! Code generation for the `HTMLElement` subclass wrapping a component.
+
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::{ExprFactory, private_ident};
+
+use crate::component::Component;
+use crate::config::Config;
+use crate::imports::RuntimeImports;
+use crate::options::ShadowRoot;
+use crate::props::ComponentProp;
+use crate::types::PropKind;
+use crate::utils::{assign, block, call, const_decl, ident, member, method, not, object, str_lit};
+
+/// Emits `class <Name>Element extends HTMLElement { ... }` followed by the
+/// `customElements.define(...)` call registering it.
+pub(crate) fn define_element(
+    component: &Component,
+    config: &Config,
+    imports: &mut RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Vec<ModuleItem> {
+    let mut body = vec![];
+
+    if !component.props.is_empty() {
+        body.push(observed_attributes(component));
+    }
+    if component.form_associated {
+        // static formAssociated = true;
+        body.push(ClassMember::ClassProp(ClassProp {
+            key: PropName::Ident("formAssociated".into()),
+            value: Some(Box::new(Lit::Bool(true.into()).into())),
+            is_static: true,
+            ..Default::default()
+        }));
+    }
+    body.push(props_field(component, imports));
+    let reflects = component.props.iter().any(|prop| prop.reflect);
+    if reflects {
+        body.push(ClassMember::PrivateProp(PrivateProp {
+            key: reflecting_name(),
+            value: Some(Box::new(Lit::Bool(false.into()).into())),
+            ..Default::default()
+        }));
+    }
+    if component.lifecycle {
+        // #lifecycle = createLifecycle(this.attachInternals());
+        let internals = component.form_associated.then(|| {
+            call(
+                member(ThisExpr { span: DUMMY_SP }, "attachInternals"),
+                vec![],
+            )
+        });
+        body.push(ClassMember::PrivateProp(PrivateProp {
+            key: lifecycle_name(),
+            value: Some(Box::new(call(
+                imports.get("createLifecycle"),
+                internals.into_iter().collect(),
+            ))),
+            ..Default::default()
+        }));
+    }
+    if rerenders(component, config) {
+        // #root = this.attachShadow({ mode: "open" });
+        // #rendered = false;
+        if let Some(shadow) = &component.shadow {
+            body.push(ClassMember::PrivateProp(PrivateProp {
+                key: root_name(),
+                value: Some(Box::new(attach_shadow(shadow))),
+                ..Default::default()
+            }));
+        }
+        body.push(ClassMember::PrivateProp(PrivateProp {
+            key: rendered_name(),
+            value: Some(Box::new(Lit::Bool(false.into()).into())),
+            ..Default::default()
+        }));
+    }
+    if !config.defer_render || !component.props.is_empty() {
+        body.push(constructor(component, config, imports, unresolved));
+    }
+    body.extend(connected_callback(component, config, imports, unresolved));
+    if component.lifecycle {
+        body.push(disconnected_callback(component, imports));
+    }
+    if component.form_associated {
+        body.extend(form_callbacks(imports));
+    }
+    if !component.props.is_empty() {
+        body.push(attribute_changed_callback(component, reflects, unresolved));
+    }
+    body.extend(component.props.iter().flat_map(accessors));
+
+    let class_decl = ClassDecl {
+        ident: component.class_ident.clone(),
+        declare: false,
+        class: Box::new(Class {
+            body,
+            super_class: Some(ident(unresolved, "HTMLElement").into()),
+            ..Default::default()
+        }),
+    };
+
+    // customElements.define("<tag>", <Class>);
+    let registry = || ident(unresolved, "customElements");
+    let mut define_call = call(
+        member(registry(), "define"),
+        vec![
+            str_lit(&component.tag_name),
+            component.class_ident.clone().into(),
+        ],
+    );
+    // re-evaluating the module on hot reload must not define the tag twice
+    if config.dev {
+        define_call = Expr::Bin(BinExpr {
+            span: DUMMY_SP,
+            op: op!("||"),
+            left: Box::new(call(
+                member(registry(), "get"),
+                vec![str_lit(&component.tag_name)],
+            )),
+            right: Box::new(define_call),
+        });
+    }
+
+    vec![
+        ModuleItem::Stmt(class_decl.into()),
+        ModuleItem::Stmt(define_call.into_stmt()),
+    ]
+}
+
+/// `static observedAttributes = ["initial", ...];`
+fn observed_attributes(component: &Component) -> ClassMember {
+    ClassMember::ClassProp(ClassProp {
+        key: PropName::Ident("observedAttributes".into()),
+        value: Some(Box::new(Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: component
+                .props
+                .iter()
+                .map(|prop| Some(str_lit(&prop.attr).as_arg()))
+                .collect(),
+        }))),
+        is_static: true,
+        ..Default::default()
+    })
+}
+
+/// `#props = { initial: signal(), ... };`
+fn props_field(component: &Component, imports: &mut RuntimeImports) -> ClassMember {
+    let store = component
+        .props
+        .iter()
+        .map(|prop| (&*prop.name, call(imports.get("signal"), vec![])))
+        .collect();
+
+    ClassMember::PrivateProp(PrivateProp {
+        key: props_name(),
+        value: Some(Box::new(object(store))),
+        ..Default::default()
+    })
+}
+
+/// constructor() {
+///   super();
+///   for (const name of ["initial"]) { ... }
+///   const _n = Component(this.#props);
+///   this.attachShadow({ mode: "open" }).appendChild(_n);
+/// }
+///
+/// Upgrades the props set before the element was defined, then renders
+/// unless `defer_render`. Elements that render again after a disconnect
+/// first set `#rendered`.
+fn constructor(
+    component: &Component,
+    config: &Config,
+    imports: &mut RuntimeImports,
+    unresolved: SyntaxContext,
+) -> ClassMember {
+    let mut body = vec![
+        CallExpr {
+            callee: Callee::Super(Super { span: DUMMY_SP }),
+            ..Default::default()
+        }
+        .into_stmt(),
+    ];
+    if !component.props.is_empty() {
+        body.push(upgrade_props(component, unresolved));
+    }
+    // with `defer_render` the component renders in `connectedCallback`
+    if !config.defer_render {
+        if rerenders(component, config) {
+            let rendered = assign(this_private(rendered_name()), Lit::Bool(true.into()).into());
+            body.push(rendered.into_stmt());
+        }
+        body.extend(render(component, config, imports, unresolved));
+    }
+
+    ClassMember::Constructor(Constructor {
+        key: PropName::Ident("constructor".into()),
+        body: Some(block(body)),
+        ..Default::default()
+    })
+}
+
+/// ```js
+/// for (const name of ["initial", ...]) {
+///   if (Object.hasOwn(this, name)) {
+///     const value = this[name];
+///     delete this[name];
+///     this[name] = value;
+///   }
+/// }
+/// ```
+///
+/// A prop set on the element before its definition loaded is an own property
+/// shadowing the accessor; it is passed on to the accessor instead.
+fn upgrade_props(component: &Component, unresolved: SyntaxContext) -> Stmt {
+    let name = private_ident!("name");
+    let value = private_ident!("value");
+    let prop = || {
+        Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(ThisExpr { span: DUMMY_SP }.into()),
+            prop: MemberProp::Computed(ComputedPropName {
+                span: DUMMY_SP,
+                expr: Box::new(name.clone().into()),
+            }),
+        })
+    };
+    let has_own = call(
+        member(ident(unresolved, "Object"), "hasOwn"),
+        vec![ThisExpr { span: DUMMY_SP }.into(), name.clone().into()],
+    );
+    let delete = Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: op!("delete"),
+        arg: Box::new(prop()),
+    });
+    let upgrade = Stmt::If(IfStmt {
+        span: DUMMY_SP,
+        test: Box::new(has_own),
+        cons: Box::new(Stmt::Block(block(vec![
+            const_decl(value.clone(), prop()),
+            delete.into_stmt(),
+            assign(prop(), value.into()).into_stmt(),
+        ]))),
+        alt: None,
+    });
+
+    let names = component
+        .props
+        .iter()
+        .map(|prop| Some(str_lit(&prop.name).as_arg()))
+        .collect();
+    Stmt::ForOf(ForOfStmt {
+        span: DUMMY_SP,
+        is_await: false,
+        left: ForHead::VarDecl(Box::new(VarDecl {
+            span: DUMMY_SP,
+            kind: VarDeclKind::Const,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: name.clone().into(),
+                init: None,
+                definite: false,
+            }],
+            ..Default::default()
+        })),
+        right: Box::new(Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: names,
+        })),
+        body: Box::new(Stmt::Block(block(vec![upgrade]))),
+    })
+}
+
+/// Renders the component into the shadow root and starts reflecting props:
+///
+/// ```js
+/// const _n = Component(this.#props);
+/// this.attachShadow({ mode: "open" }).appendChild(_n);
+/// ```
+///
+/// With a lifecycle the component is called through `render`. Styles are
+/// applied and delegated events registered on the shadow root around the
+/// append. When the element can render again the shadow root is the `#root`
+/// field; light DOM components render into the element itself.
+fn render(
+    component: &Component,
+    config: &Config,
+    imports: &mut RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Vec<Stmt> {
+    let node = private_ident!("_n");
+    let mut args = vec![this_props()];
+    if !component.events.is_empty() {
+        // the component dispatches its events on the element
+        args.push(ThisExpr { span: DUMMY_SP }.into());
+    }
+    let mut render = call(component.fn_ident.clone(), args);
+    if component.lifecycle {
+        // render(this.#lifecycle, () => Component(this.#props))
+        render = call(
+            imports.get("render"),
+            vec![
+                this_private(lifecycle_name()),
+                Box::new(render).into_lazy_arrow(vec![]).into(),
+            ],
+        );
+    }
+
+    let mut body = vec![const_decl(node.clone(), render)];
+    let (root, attached) = match &component.shadow {
+        None => (ThisExpr { span: DUMMY_SP }.into(), true),
+        Some(_) if rerenders(component, config) => (this_private(root_name()), true),
+        Some(shadow) => (attach_shadow(shadow), false),
+    };
+    if component.styles.is_empty() && component.delegated_events.is_empty() {
+        body.push(call(member(root, "appendChild"), vec![node.into()]).into_stmt());
+    } else if attached {
+        body.extend(shadow_root(
+            component, config, root, node, imports, unresolved,
+        ));
+    } else {
+        let local = private_ident!("_root");
+        body.push(const_decl(local.clone(), root));
+        body.extend(shadow_root(
+            component,
+            config,
+            local.into(),
+            node,
+            imports,
+            unresolved,
+        ));
+    }
+    body.extend(
+        component
+            .props
+            .iter()
+            .filter(|prop| prop.reflect)
+            .map(|prop| reflect(prop, component.lifecycle, imports, unresolved)),
+    );
+    body
+}
+
+/// `this.attachShadow({ mode: "open", delegatesFocus: true })`
+fn attach_shadow(shadow: &ShadowRoot) -> Expr {
+    let mut init = vec![("mode", str_lit(shadow.mode.as_str()))];
+    if shadow.delegates_focus {
+        init.push(("delegatesFocus", Lit::Bool(true.into()).into()));
+    }
+    if let Some(assignment) = shadow.slot_assignment {
+        init.push(("slotAssignment", str_lit(assignment.as_str())));
+    }
+    call(
+        member(ThisExpr { span: DUMMY_SP }, "attachShadow"),
+        vec![object(init)],
+    )
+}
+
+/// connectedCallback() {
+///   if (!this.#rendered) {
+///     this.#rendered = true;
+///     const _n = Component(this.#props);
+///     this.#root.appendChild(_n);
+///   }
+///   connect(this.#lifecycle);
+/// }
+///
+/// Renders on the first connection with `defer_render`, and again on the
+/// first one after `disconnect` disposed the render. Runs the mount hooks of
+/// a lifecycle on every connection.
+fn connected_callback(
+    component: &Component,
+    config: &Config,
+    imports: &mut RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Option<ClassMember> {
+    let mut body = vec![];
+    if rerenders(component, config) {
+        let rendered = || this_private(rendered_name());
+        let mut once = vec![assign(rendered(), Lit::Bool(true.into()).into()).into_stmt()];
+        once.extend(render(component, config, imports, unresolved));
+        body.push(Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::new(not(rendered())),
+            cons: Box::new(Stmt::Block(block(once))),
+            alt: None,
+        }));
+    }
+    if component.lifecycle {
+        let connect = call(imports.get("connect"), vec![this_private(lifecycle_name())]);
+        body.push(connect.into_stmt());
+    }
+
+    (!body.is_empty()).then(|| method(MethodKind::Method, "connectedCallback", false, vec![], body))
+}
+
+/// disconnectedCallback() {
+///   disconnect(this.#lifecycle, this, () => {
+///     this.#rendered = false;
+///     this.#root.replaceChildren();
+///   });
+/// }
+///
+/// Moving the element, i.e. detaching and attaching it again within the same
+/// task as a list reorder or a drag-and-drop library does, keeps the render
+/// and the state of the component. An element left detached past that is
+/// removed: once `disconnect` has disposed the effects the rendered nodes no
+/// longer update, so they are dropped and the next connection renders again,
+/// with fresh component state.
+fn disconnected_callback(component: &Component, imports: &mut RuntimeImports) -> ClassMember {
+    let root = match component.shadow {
+        Some(_) => this_private(root_name()),
+        None => ThisExpr { span: DUMMY_SP }.into(),
+    };
+    let reset = ArrowExpr {
+        body: Box::new(BlockStmtOrExpr::BlockStmt(block(vec![
+            assign(
+                this_private(rendered_name()),
+                Lit::Bool(false.into()).into(),
+            )
+            .into_stmt(),
+            call(member(root, "replaceChildren"), vec![]).into_stmt(),
+        ]))),
+        ..Default::default()
+    };
+    let disconnect = call(
+        imports.get("disconnect"),
+        vec![
+            this_private(lifecycle_name()),
+            ThisExpr { span: DUMMY_SP }.into(),
+            reset.into(),
+        ],
+    );
+    method(
+        MethodKind::Method,
+        "disconnectedCallback",
+        false,
+        vec![],
+        vec![disconnect.into_stmt()],
+    )
+}
+
+/// formResetCallback() { resetForm(this.#lifecycle); }
+/// formStateRestoreCallback(state, mode) { restoreForm(this.#lifecycle, state, mode); }
+fn form_callbacks(imports: &mut RuntimeImports) -> [ClassMember; 2] {
+    let reset = call(
+        imports.get("resetForm"),
+        vec![this_private(lifecycle_name())],
+    );
+    let state = private_ident!("state");
+    let mode = private_ident!("mode");
+    let restore = call(
+        imports.get("restoreForm"),
+        vec![
+            this_private(lifecycle_name()),
+            state.clone().into(),
+            mode.clone().into(),
+        ],
+    );
+
+    [
+        method(
+            MethodKind::Method,
+            "formResetCallback",
+            false,
+            vec![],
+            vec![reset.into_stmt()],
+        ),
+        method(
+            MethodKind::Method,
+            "formStateRestoreCallback",
+            false,
+            vec![state.into(), mode.into()],
+            vec![restore.into_stmt()],
+        ),
+    ]
+}
+
+/// Fills the shadow root `root` (or the element, without one) with the
+/// styles and the rendered `node`:
+///
+/// ```js
+/// _root.adoptedStyleSheets = [_sheet];
+/// _root.appendChild(_n);
+/// delegateEvents(_root, ["click"]);
+/// ```
+fn shadow_root(
+    component: &Component,
+    config: &Config,
+    root: Expr,
+    node: Ident,
+    imports: &mut RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Vec<Stmt> {
+    let array = |elems: Vec<Expr>| {
+        Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: elems.into_iter().map(|elem| Some(elem.as_arg())).collect(),
+        })
+    };
+    let mut stmts = vec![];
+
+    let mut children = vec![];
+    if config.style_tags {
+        // const _style = document.createElement("style");
+        // _style.textContent = styles;
+        for text in &component.styles {
+            let style = private_ident!("_style");
+            let create = member(ident(unresolved, "document"), "createElement");
+            stmts.push(const_decl(
+                style.clone(),
+                call(create, vec![str_lit("style")]),
+            ));
+            stmts.push(
+                assign(member(style.clone(), "textContent"), text.clone().into()).into_stmt(),
+            );
+            children.push(style.into());
+        }
+    } else if !component.styles.is_empty() {
+        let sheets = component.styles.iter().cloned().map(Expr::from).collect();
+        stmts.push(assign(member(root.clone(), "adoptedStyleSheets"), array(sheets)).into_stmt());
+    }
+
+    children.push(node.into());
+    let append = if children.len() == 1 {
+        "appendChild"
+    } else {
+        "append"
+    };
+    stmts.push(call(member(root.clone(), append), children).into_stmt());
+
+    if !component.delegated_events.is_empty() {
+        let events = component
+            .delegated_events
+            .iter()
+            .map(|event| str_lit(event))
+            .collect();
+        stmts.push(
+            call(
+                imports.get("delegateEvents"),
+                vec![root.clone(), array(events)],
+            )
+            .into_stmt(),
+        );
+    }
+
+    stmts
+}
+
+/// attributeChangedCallback(name, _old, value) {
+///   if (this.#reflecting) return;
+///   switch (name) {
+///     case "initial":
+///       this.#props.initial.value = value === null ? null : Number(value);
+///       break;
+///   }
+/// }
+fn attribute_changed_callback(
+    component: &Component,
+    reflects: bool,
+    unresolved: SyntaxContext,
+) -> ClassMember {
+    let name = private_ident!("name");
+    let old = private_ident!("_old");
+    let value = private_ident!("value");
+
+    let cases = component
+        .props
+        .iter()
+        .map(|prop| SwitchCase {
+            span: DUMMY_SP,
+            test: Some(Box::new(str_lit(&prop.attr))),
+            cons: vec![
+                assign(
+                    member(member(this_props(), &prop.name), "value"),
+                    convert(prop.kind, &value, unresolved),
+                )
+                .into_stmt(),
+                Stmt::Break(BreakStmt {
+                    span: DUMMY_SP,
+                    label: None,
+                }),
+            ],
+        })
+        .collect();
+
+    let switch = Stmt::Switch(SwitchStmt {
+        span: DUMMY_SP,
+        discriminant: Box::new(name.clone().into()),
+        cases,
+    });
+
+    // writes made by `reflect` must not parse the value back into the prop
+    let mut stmts = vec![];
+    if reflects {
+        stmts.push(Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::new(this_private(reflecting_name())),
+            cons: Box::new(Stmt::Return(ReturnStmt {
+                span: DUMMY_SP,
+                arg: None,
+            })),
+            alt: None,
+        }));
+    }
+    stmts.push(switch);
+
+    method(
+        MethodKind::Method,
+        "attributeChangedCallback",
+        false,
+        vec![name.into(), old.into(), value.into()],
+        stmts,
+    )
+}
+
+/// Writes the prop back to its attribute whenever it is set:
+///
+/// ```js
+/// effect(() => {
+///   const value = this.#props.open.value;
+///   if (value !== undefined) {
+///     this.#reflecting = true;
+///     this.toggleAttribute("open", !!value);
+///     this.#reflecting = false;
+///   }
+/// });
+/// ```
+///
+/// Props nobody has set yet are left alone, so the attributes of an upgraded
+/// element survive the render. With a lifecycle the effect is disposed with
+/// the render, through `this.#lifecycle.effects.push(effect(...))`.
+fn reflect(
+    prop: &ComponentProp,
+    lifecycle: bool,
+    imports: &mut RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Stmt {
+    let value = private_ident!("value");
+    let this = || Box::new(Expr::This(ThisExpr { span: DUMMY_SP }));
+    let attr = || str_lit(&prop.attr);
+    let set_reflecting =
+        |on: bool| assign(this_private(reflecting_name()), Lit::Bool(on.into()).into()).into_stmt();
+
+    let write = match prop.kind {
+        PropKind::Boolean => call(
+            member(this(), "toggleAttribute"),
+            vec![attr(), not(not(value.clone().into()))],
+        )
+        .into_stmt(),
+        kind => {
+            let serialize = match kind {
+                PropKind::Json => member(ident(unresolved, "JSON"), "stringify"),
+                _ => ident(unresolved, "String").into(),
+            };
+            Stmt::If(IfStmt {
+                span: DUMMY_SP,
+                test: Box::new(is_null(&value, op!("==="))),
+                cons: Box::new(call(member(this(), "removeAttribute"), vec![attr()]).into_stmt()),
+                alt: Some(Box::new(
+                    call(
+                        member(this(), "setAttribute"),
+                        vec![attr(), call(serialize, vec![value.clone().into()])],
+                    )
+                    .into_stmt(),
+                )),
+            })
+        }
+    };
+
+    let body = vec![
+        const_decl(
+            value.clone(),
+            member(member(this_props(), &prop.name), "value"),
+        ),
+        Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::new(Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op: op!("!=="),
+                left: Box::new(value.into()),
+                right: Box::new(ident(unresolved, "undefined").into()),
+            })),
+            cons: Box::new(Stmt::Block(block(vec![
+                set_reflecting(true),
+                write,
+                set_reflecting(false),
+            ]))),
+            alt: None,
+        }),
+    ];
+
+    let effect = ArrowExpr {
+        body: Box::new(BlockStmtOrExpr::BlockStmt(block(body))),
+        ..Default::default()
+    };
+    let mut effect = call(imports.get("effect"), vec![effect.into()]);
+    if lifecycle {
+        let effects = member(this_private(lifecycle_name()), "effects");
+        effect = call(member(effects, "push"), vec![effect]);
+    }
+    effect.into_stmt()
+}
+
+/// get initial() { return this.#props.initial.value; }
+/// set initial(value) { this.#props.initial.value = value; }
+fn accessors(prop: &ComponentProp) -> [ClassMember; 2] {
+    let signal = || member(member(this_props(), &prop.name), "value");
+    let value = private_ident!("value");
+
+    [
+        method(
+            MethodKind::Getter,
+            &prop.name,
+            false,
+            vec![],
+            vec![signal().into_return_stmt().into()],
+        ),
+        method(
+            MethodKind::Setter,
+            &prop.name,
+            false,
+            vec![value.clone().into()],
+            vec![assign(signal(), value.into()).into_stmt()],
+        ),
+    ]
+}
+
+/// Parses the attribute `value` (`null` when removed) according to `kind`.
+fn convert(kind: PropKind, value: &Ident, unresolved: SyntaxContext) -> Expr {
+    let parse = |parser: Expr| {
+        // value === null ? null : parser(value)
+        Expr::Cond(CondExpr {
+            span: DUMMY_SP,
+            test: Box::new(is_null(value, op!("==="))),
+            cons: Box::new(Lit::Null(Null { span: DUMMY_SP }).into()),
+            alt: Box::new(call(parser, vec![value.clone().into()])),
+        })
+    };
+
+    match kind {
+        PropKind::String => value.clone().into(),
+        PropKind::Number => parse(ident(unresolved, "Number").into()),
+        PropKind::Boolean => is_null(value, op!("!==")),
+        PropKind::Json => parse(member(ident(unresolved, "JSON"), "parse")),
+    }
+}
+
+/// `value === null` / `value !== null`
+fn is_null(value: &Ident, op: BinaryOp) -> Expr {
+    Expr::Bin(BinExpr {
+        span: DUMMY_SP,
+        op,
+        left: Box::new(value.clone().into()),
+        right: Box::new(Lit::Null(Null { span: DUMMY_SP }).into()),
+    })
+}
+
+fn props_name() -> PrivateName {
+    PrivateName {
+        span: DUMMY_SP,
+        name: "props".into(),
+    }
+}
+
+/// Whether the element renders in `connectedCallback`: on the first connection
+/// with `defer_render`, and after a disconnect disposed the render of a
+/// lifecycle.
+fn rerenders(component: &Component, config: &Config) -> bool {
+    config.defer_render || component.lifecycle
+}
+
+/// Shadow root of an element that renders in `connectedCallback`.
+fn root_name() -> PrivateName {
+    PrivateName {
+        span: DUMMY_SP,
+        name: "root".into(),
+    }
+}
+
+/// Set once `connectedCallback` has rendered the component.
+fn rendered_name() -> PrivateName {
+    PrivateName {
+        span: DUMMY_SP,
+        name: "rendered".into(),
+    }
+}
+
+/// Hooks and effects of the element, see [`crate::lifecycle`].
+fn lifecycle_name() -> PrivateName {
+    PrivateName {
+        span: DUMMY_SP,
+        name: "lifecycle".into(),
+    }
+}
+
+/// Set while `reflect` writes an attribute.
+fn reflecting_name() -> PrivateName {
+    PrivateName {
+        span: DUMMY_SP,
+        name: "reflecting".into(),
+    }
+}
+
+/// `this.#props`
+fn this_props() -> Expr {
+    this_private(props_name())
+}
+
+/// `this.#<name>`
+fn this_private(name: PrivateName) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(ThisExpr { span: DUMMY_SP }.into()),
+        prop: MemberProp::PrivateName(name),
+    })
+}
@@ -0,0 +1,294 @@
+//! Custom events dispatched by a component.
+//!
+//! A component declares the events it dispatches and their detail types with
+//! `defineEvents`, or calls the runtime's `emit` directly:
+//!
+//! ```tsx
+//! export default function Stepper() {
+//!   const emit = defineEvents<{ change: number; reset: void }>();
+//!   return <button onClick={() => emit("change", 1)}>+</button>;
+//! }
+//! ```
+//!
+//! The element passes itself to the component, which dispatches the events
+//! on it:
+//!
+//! ```js
+//! function Stepper(__props, __host) {
+//!   // onClick: () => __host.dispatchEvent(new CustomEvent("change", {
+//!   //   detail: 1, bubbles: true, composed: true }))
+//! }
+//! ```
+//!
+//! The events bubble out of the shadow root, and their names and detail types
+//! are recorded on the component.
+
+use swc_core::common::comments::Comments;
+use swc_core::common::{DUMMY_SP, Span, Spanned, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::atoms::Atom;
+use swc_core::ecma::codegen::to_code;
+use swc_core::ecma::utils::private_ident;
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::imports::RuntimeImports;
+use crate::jsdoc;
+use crate::types::TypeDecls;
+use crate::utils::{call, emit_error, ident, member, object, str_lit};
+
+/// Runtime helper returning a typed `emit`.
+const DEFINE_EVENTS: &str = "defineEvents";
+
+/// Runtime helper dispatching an undeclared event.
+const EMIT: &str = "emit";
+
+/// An event dispatched by a component.
+#[derive(Debug, Clone)]
+pub(crate) struct ComponentEvent {
+    /// Event name, e.g. `change`.
+    pub name: Atom,
+    /// TypeScript type of `event.detail`, `None` for events without detail.
+    pub detail: Option<String>,
+    /// JSDoc of the event in the `defineEvents` type.
+    pub description: Option<String>,
+}
+
+/// Rewrites the `emit` calls of `function` into dispatches on the element,
+/// passed as its second parameter, and returns the events it dispatches.
+pub(crate) fn lower_emits(
+    function: &mut Function,
+    types: &TypeDecls,
+    comments: &dyn Comments,
+    imports: &RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Vec<ComponentEvent> {
+    let Some(body) = &mut function.body else {
+        return vec![];
+    };
+
+    let mut emits = Emits {
+        host: private_ident!("__host"),
+        unresolved,
+        define_events: imports.find(DEFINE_EVENTS).map(Ident::to_id),
+        emit: imports.find(EMIT).map(Ident::to_id),
+        declared: None,
+        events: vec![],
+    };
+
+    // const emit = defineEvents<{ change: number }>();
+    body.stmts.retain(|stmt| match emits.declaration(stmt) {
+        Some((local, ty)) => {
+            emits.declare(local, ty, types, comments);
+            false
+        }
+        None => true,
+    });
+    body.visit_mut_with(&mut emits);
+
+    if emits.events.is_empty() {
+        return vec![];
+    }
+    if function.params.is_empty() {
+        function
+            .params
+            .push(Param::from(Pat::from(private_ident!("__props"))));
+    }
+    function.params.push(Param::from(Pat::from(emits.host)));
+    emits.events
+}
+
+struct Emits {
+    /// Second parameter of the component, the element.
+    host: Ident,
+    unresolved: SyntaxContext,
+    define_events: Option<Id>,
+    emit: Option<Id>,
+    /// Local `emit` returned by `defineEvents`, and its events.
+    declared: Option<(Id, Vec<ComponentEvent>)>,
+    /// Events dispatched or declared so far.
+    events: Vec<ComponentEvent>,
+}
+
+impl Emits {
+    /// `const <local> = defineEvents<ty>();`
+    fn declaration<'a>(&self, stmt: &'a Stmt) -> Option<(Id, Option<&'a TsType>)> {
+        let Stmt::Decl(Decl::Var(var)) = stmt else {
+            return None;
+        };
+        let [
+            VarDeclarator {
+                name: Pat::Ident(local),
+                init: Some(init),
+                ..
+            },
+        ] = var.decls.as_slice()
+        else {
+            return None;
+        };
+        let Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            type_args,
+            ..
+        }) = &**init
+        else {
+            return None;
+        };
+        if !self.is(callee, &self.define_events) {
+            return None;
+        }
+        let ty = type_args
+            .as_ref()
+            .and_then(|args| args.params.first())
+            .map(|ty| &**ty);
+        Some((local.to_id(), ty))
+    }
+
+    fn declare(
+        &mut self,
+        local: Id,
+        ty: Option<&TsType>,
+        types: &TypeDecls,
+        comments: &dyn Comments,
+    ) {
+        let events: Vec<_> = ty
+            .and_then(|ty| types.members(ty, 0))
+            .into_iter()
+            .flatten()
+            .filter_map(|member| {
+                let TsTypeElement::TsPropertySignature(prop) = member else {
+                    return None;
+                };
+                let name = match &*prop.key {
+                    Expr::Ident(ident) => ident.sym.clone(),
+                    Expr::Lit(Lit::Str(str)) => str.value.clone(),
+                    _ => return None,
+                };
+                let detail = prop
+                    .type_ann
+                    .as_ref()
+                    .map(|ann| &*ann.type_ann)
+                    .filter(|ty| !is_void(ty))
+                    .map(to_code);
+                let description = jsdoc::description(comments, prop.span_lo());
+                Some(ComponentEvent {
+                    name,
+                    detail,
+                    description,
+                })
+            })
+            .collect();
+        self.events.extend(events.iter().cloned());
+        self.declared = Some((local, events));
+    }
+
+    fn is(&self, expr: &Expr, id: &Option<Id>) -> bool {
+        matches!(expr, Expr::Ident(ident) if id.as_ref() == Some(&ident.to_id()))
+    }
+
+    /// Name of the event dispatched by `emit(name, detail)`, reporting the
+    /// ones `declared` does not list.
+    fn event_name(
+        &mut self,
+        args: &[ExprOrSpread],
+        declared: Option<&[ComponentEvent]>,
+        span: Span,
+    ) -> Option<Atom> {
+        let name = match args.first() {
+            Some(ExprOrSpread { spread: None, expr }) => match &**expr {
+                Expr::Lit(Lit::Str(name)) => Some(name.value.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(name) = name else {
+            emit_error(
+                span,
+                "the event name passed to `emit` must be a string literal",
+            );
+            return None;
+        };
+
+        match declared {
+            Some(declared) if !declared.iter().any(|event| event.name == name) => emit_error(
+                span,
+                &format!("`{name}` is not one of the events declared by `defineEvents`"),
+            ),
+            Some(_) => {}
+            None if self.events.iter().any(|event| event.name == name) => {}
+            None => self.events.push(ComponentEvent {
+                name: name.clone(),
+                detail: None,
+                description: None,
+            }),
+        }
+        Some(name)
+    }
+
+    /// `__host.dispatchEvent(new CustomEvent(name, { detail, bubbles: true, composed: true }))`
+    fn dispatch(&self, name: &str, detail: Option<Box<Expr>>) -> Expr {
+        let mut init = vec![];
+        if let Some(detail) = detail {
+            init.push(("detail", *detail));
+        }
+        init.push(("bubbles", true.into()));
+        init.push(("composed", true.into()));
+        let event = NewExpr {
+            span: DUMMY_SP,
+            callee: Box::new(ident(self.unresolved, "CustomEvent").into()),
+            args: Some(vec![str_lit(name).into(), object(init).into()]),
+            ..Default::default()
+        };
+        call(
+            member(self.host.clone(), "dispatchEvent"),
+            vec![event.into()],
+        )
+    }
+}
+
+impl VisitMut for Emits {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        let Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            args,
+            span,
+            ..
+        }) = expr
+        else {
+            return;
+        };
+        let declared = match &self.declared {
+            Some((local, events)) if self.is(callee, &Some(local.clone())) => Some(events.clone()),
+            _ if self.is(callee, &self.emit) => None,
+            _ => return,
+        };
+        if let Some(name) = self.event_name(args, declared.as_deref(), *span) {
+            let detail = args.get(1).map(|arg| arg.expr.clone());
+            *expr = self.dispatch(&name, detail);
+        }
+    }
+
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        if let Callee::Expr(callee) = &call.callee
+            && self.is(callee, &self.define_events)
+        {
+            emit_error(
+                call.span(),
+                "`defineEvents` must initialize a constant at the top level of the component",
+            );
+        }
+        call.visit_mut_children_with(self);
+    }
+}
+
+/// `void` or `undefined`: the event has no detail.
+fn is_void(ty: &TsType) -> bool {
+    matches!(
+        ty,
+        TsType::TsKeywordType(TsKeywordType {
+            kind: TsKeywordTypeKind::TsVoidKeyword | TsKeywordTypeKind::TsUndefinedKeyword,
+            ..
+        })
+    )
+}
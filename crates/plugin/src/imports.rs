@@ -0,0 +1,119 @@
+//! Tracks the runtime helpers (`@fluxel/core` by default) referenced by
+//! generated code.
+
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::atoms::Atom;
+use swc_core::ecma::utils::private_ident;
+
+/// Named imports from the runtime module, both the ones already present in the
+/// source and the ones requested by code generation.
+pub(crate) struct RuntimeImports {
+    /// Module the generated code imports its runtime helpers from.
+    source: Atom,
+    existing: Vec<(Atom, Ident)>,
+    added: Vec<(Atom, Ident)>,
+}
+
+impl RuntimeImports {
+    /// Collects the value imports of the runtime module `source` in `m`.
+    pub fn from_module(m: &Module, source: &str) -> Self {
+        let source = Atom::from(source);
+        let existing = runtime_imports(m, &source)
+            .flat_map(|import| &import.specifiers)
+            .filter_map(|specifier| match specifier {
+                ImportSpecifier::Named(named) if !named.is_type_only => {
+                    let imported = match &named.imported {
+                        Some(ModuleExportName::Ident(ident)) => ident.sym.clone(),
+                        Some(ModuleExportName::Str(str)) => str.value.clone(),
+                        None => named.local.sym.clone(),
+                    };
+                    Some((imported, named.local.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            source,
+            existing,
+            added: vec![],
+        }
+    }
+
+    /// Local binding of the helper `name` if the source imports it.
+    pub fn find(&self, name: &str) -> Option<&Ident> {
+        self.existing
+            .iter()
+            .chain(&self.added)
+            .find(|(imported, _)| imported == name)
+            .map(|(_, local)| local)
+    }
+
+    /// Local binding of the helper `name`, importing it if needed.
+    pub fn get(&mut self, name: &str) -> Ident {
+        if let Some(local) = self.find(name) {
+            return local.clone();
+        }
+
+        let local = private_ident!(name);
+        self.added.push((name.into(), local.clone()));
+        local
+    }
+
+    /// Adds the requested helpers to the runtime import of `m`, creating the
+    /// import declaration when the module has none.
+    pub fn inject(self, m: &mut Module) {
+        if self.added.is_empty() {
+            return;
+        }
+
+        let specifiers = self.added.into_iter().map(|(_, local)| {
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local,
+                imported: None,
+                is_type_only: false,
+            })
+        });
+
+        // `import * as core from "..."` cannot take named specifiers
+        let existing = m.body.iter_mut().find_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                if is_runtime_import(import, &self.source)
+                    && !import.specifiers.iter().any(ImportSpecifier::is_namespace) =>
+            {
+                Some(import)
+            }
+            _ => None,
+        });
+
+        match existing {
+            Some(import) => import.specifiers.extend(specifiers),
+            None => m.body.insert(
+                0,
+                ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                    span: DUMMY_SP,
+                    specifiers: specifiers.collect(),
+                    src: Box::new(self.source.into()),
+                    type_only: false,
+                    with: None,
+                    phase: Default::default(),
+                })),
+            ),
+        }
+    }
+}
+
+fn runtime_imports<'a>(m: &'a Module, source: &Atom) -> impl Iterator<Item = &'a ImportDecl> {
+    m.body.iter().filter_map(move |item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if is_runtime_import(import, source) => {
+            Some(import)
+        }
+        _ => None,
+    })
+}
+
+fn is_runtime_import(import: &ImportDecl, source: &Atom) -> bool {
+    !import.type_only && import.src.value == *source
+}
@@ -0,0 +1,45 @@
+//! JSDoc annotations read from the comments attached to declarations.
+
+use swc_core::common::BytePos;
+use swc_core::common::comments::{CommentKind, Comments};
+
+/// Value of the block tag `@<name>` in the JSDoc leading `pos`; `Some("")`
+/// for a bare tag such as `@reflect`.
+pub(crate) fn tag(comments: &dyn Comments, pos: BytePos, name: &str) -> Option<String> {
+    let leading = comments.get_leading(pos)?;
+
+    leading
+        .iter()
+        // JSDoc blocks start with `/**`
+        .filter(|comment| comment.kind == CommentKind::Block && comment.text.starts_with('*'))
+        .flat_map(|comment| comment.text.lines())
+        .find_map(|line| {
+            let line = line.trim_start().trim_start_matches('*').trim();
+            let rest = line.strip_prefix('@')?.strip_prefix(name)?;
+            // `@reflect` must not match `@reflected`
+            match rest.chars().next() {
+                None => Some(String::new()),
+                Some(c) if c.is_whitespace() => Some(rest.trim().to_string()),
+                _ => None,
+            }
+        })
+}
+
+/// Text of the JSDoc leading `pos` up to its first block tag, `None` when it
+/// has none.
+pub(crate) fn description(comments: &dyn Comments, pos: BytePos) -> Option<String> {
+    let leading = comments.get_leading(pos)?;
+    // the block closest to the declaration documents it
+    let doc = leading
+        .iter()
+        .rfind(|comment| comment.kind == CommentKind::Block && comment.text.starts_with('*'))?;
+
+    let lines: Vec<_> = doc
+        .text
+        .lines()
+        .map(|line| line.trim_start().trim_start_matches('*').trim())
+        .take_while(|line| !line.starts_with('@'))
+        .collect();
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
@@ -0,0 +1,322 @@
+//! `<Show>` and `<For>` from the runtime module, and `.map()` over signals.
+//!
+//! ```jsx
+//! <Show when={open} fallback={<p>Closed</p>}><p>Open</p></Show>
+//! <For each={todos}>{(todo) => <li>{todo.title}</li>}</For>
+//! ```
+//!
+//! become calls of the anchor-based runtime helpers, with the condition and
+//! the list read lazily so they are tracked by the helper's effect:
+//!
+//! ```js
+//! createShow(() => open.value, () => { ...; return _p; }, () => { ...; return _p1; });
+//! createFor(() => todos.value, (todo) => { ...; return _li; });
+//! ```
+//!
+//! `{todos.value.map((todo) => <li key={todo.id}>...</li>)}` compiles to the
+//! same list. Rows are keyed by the `key` of the returned element, if any:
+//!
+//! ```js
+//! createFor(() => todos.value, (todo) => { ...; return _li; }, (todo) => todo.id);
+//! ```
+
+use swc_core::common::{DUMMY_SP, Span};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::{ExprFactory, collect_decls};
+use swc_core::ecma::visit::VisitMutWith;
+
+use super::{JsxLowering, is_jsx, is_map_call, jsx_text, reads};
+use crate::utils::{block, call, emit_error};
+
+/// Control-flow component recognized by its runtime import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ControlFlow {
+    Show,
+    For,
+}
+
+impl JsxLowering<'_> {
+    /// Whether `name` refers to `Show` or `For` imported from the runtime.
+    pub(super) fn control_flow(&self, name: &JSXElementName) -> Option<ControlFlow> {
+        let JSXElementName::Ident(name) = name else {
+            return None;
+        };
+        let imported = |helper| {
+            self.imports
+                .find(helper)
+                .is_some_and(|local| local.to_id() == name.to_id())
+        };
+        if imported("Show") {
+            Some(ControlFlow::Show)
+        } else if imported("For") {
+            Some(ControlFlow::For)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn lower_control_flow(&mut self, flow: ControlFlow, el: JSXElement) -> Expr {
+        let span = el.span;
+        let mut attrs = Attrs(el.opening.attrs);
+
+        match flow {
+            // createShow(() => when, () => children, () => fallback)
+            ControlFlow::Show => {
+                let Some(when) = attrs.take("when") else {
+                    return error(span, "`<Show>` needs a `when` prop");
+                };
+                let mut args = vec![self.tracked(when), self.render(children(el.children))];
+                if let Some(fallback) = attrs.take("fallback") {
+                    args.push(self.render(fallback));
+                }
+                attrs.reject_rest("Show");
+                call(self.imports.get("createShow"), args)
+            }
+            // createFor(() => each, (item, index) => row)
+            ControlFlow::For => {
+                let Some(each) = attrs.take("each") else {
+                    return error(span, "`<For>` needs an `each` prop");
+                };
+                let Some(mut row) = row_fn(el.children) else {
+                    return error(
+                        span,
+                        "`<For>` takes a single function child: `{(item) => <li>{item}</li>}`",
+                    );
+                };
+                attrs.reject_rest("For");
+                let key = match &mut row {
+                    Expr::Arrow(arrow) => key_fn(arrow),
+                    _ => None,
+                };
+                row.visit_mut_with(self);
+                let mut args = vec![self.tracked(each), row];
+                args.extend(key);
+                call(self.imports.get("createFor"), args)
+            }
+        }
+    }
+
+    /// `list.map((item) => <li key={item.id} />)` over a signal-backed `list`
+    /// as a keyed `createFor`; anything else is handed back untouched.
+    pub(super) fn keyed_list(&mut self, expr: Expr) -> Result<Expr, Expr> {
+        if !self.maps_signal(&expr) {
+            return Err(expr);
+        }
+        let Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            mut args,
+            ..
+        }) = expr
+        else {
+            unreachable!("checked by maps_signal()");
+        };
+        let (Expr::Member(MemberExpr { obj: list, .. }), Expr::Arrow(mut row)) =
+            (*callee, *args.remove(0).expr)
+        else {
+            unreachable!("checked by maps_signal()");
+        };
+
+        let key = key_fn(&mut row);
+        let mut row = Expr::Arrow(row);
+        row.visit_mut_with(self);
+
+        let mut args = vec![self.tracked(*list), row];
+        args.extend(key);
+        Ok(call(self.imports.get("createFor"), args))
+    }
+
+    /// `list.map((item) => ...)` with `list` reading a signal or a prop.
+    fn maps_signal(&self, expr: &Expr) -> bool {
+        if !is_map_call(expr) {
+            return false;
+        }
+        let Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            args,
+            ..
+        }) = expr
+        else {
+            return false;
+        };
+        let Expr::Member(MemberExpr { obj: list, .. }) = &**callee else {
+            return false;
+        };
+        matches!(args.as_slice(), [ExprOrSpread { spread: None, expr }] if expr.is_arrow())
+            && self.is_reactive(list)
+    }
+
+    /// `() => expr`, reading the value of a bare signal.
+    fn tracked(&mut self, mut expr: Expr) -> Expr {
+        expr.visit_mut_with(self);
+        let read = self.reactive_read(expr).unwrap_or_else(|expr| expr);
+        Box::new(read).into_lazy_arrow(vec![]).into()
+    }
+
+    /// `() => { ...; return _node; }` building `jsx` on every call, or
+    /// `() => expr` for anything else.
+    fn render(&mut self, mut expr: Expr) -> Expr {
+        if !is_jsx(&expr) {
+            expr.visit_mut_with(self);
+            return Box::new(expr).into_lazy_arrow(vec![]).into();
+        }
+
+        let (mut stmts, node) = self.build(expr);
+        if stmts.is_empty() {
+            return Box::new(node).into_lazy_arrow(vec![]).into();
+        }
+        stmts.push(Box::new(node).into_return_stmt().into());
+        ArrowExpr {
+            body: Box::new(BlockStmtOrExpr::BlockStmt(block(stmts))),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// `(item) => item.id`, taking the `key` off the element returned by `row`.
+/// The row renders its item once: `createFor` keeps it while the key maps to
+/// the same object and renders it again for an updated copy.
+fn key_fn(row: &mut ArrowExpr) -> Option<Expr> {
+    let key = take_key(row)?;
+    Some(Expr::Arrow(ArrowExpr {
+        params: row.params.clone(),
+        body: Box::new(BlockStmtOrExpr::Expr(key)),
+        ..Default::default()
+    }))
+}
+
+/// Removes the `key` of the element returned by `row`. The key has to be
+/// computed from the parameters alone, without rendering the row.
+fn take_key(row: &mut ArrowExpr) -> Option<Box<Expr>> {
+    let returned = match &mut *row.body {
+        BlockStmtOrExpr::Expr(body) => &mut **body,
+        BlockStmtOrExpr::BlockStmt(block) => match block.stmts.last_mut() {
+            Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })) => &mut **arg,
+            _ => return None,
+        },
+    };
+    let mut returned = returned;
+    while let Expr::Paren(paren) = returned {
+        returned = &mut paren.expr;
+    }
+    let Expr::JSXElement(el) = returned else {
+        return None;
+    };
+
+    let idx = el.opening.attrs.iter().position(|attr| {
+        matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(name),
+            ..
+        }) if name.sym == "key")
+    })?;
+    let JSXAttrOrSpread::JSXAttr(attr) = el.opening.attrs.remove(idx) else {
+        unreachable!();
+    };
+    let key = match attr.value {
+        Some(JSXAttrValue::Lit(lit)) => Box::new(Expr::Lit(lit)),
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        })) => expr,
+        _ => {
+            emit_error(attr.span, "`key` must be an expression");
+            return None;
+        }
+    };
+
+    let locals = match &*row.body {
+        BlockStmtOrExpr::BlockStmt(block) => collect_decls::<Id, _>(block),
+        BlockStmtOrExpr::Expr(_) => Default::default(),
+    };
+    if reads(&key, |id| locals.contains(id)) {
+        emit_error(
+            attr.span,
+            "`key` can only use the parameters of the `.map()` callback",
+        );
+        return None;
+    }
+    Some(key)
+}
+
+/// Props of a control-flow element, taken one by one.
+struct Attrs(Vec<JSXAttrOrSpread>);
+
+impl Attrs {
+    /// Value of the prop `name`; JSX stays JSX so it can be rendered lazily.
+    fn take(&mut self, name: &str) -> Option<Expr> {
+        let idx = self.0.iter().position(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(ident),
+                ..
+            }) if ident.sym == name)
+        })?;
+        let JSXAttrOrSpread::JSXAttr(attr) = self.0.remove(idx) else {
+            unreachable!();
+        };
+        Some(match attr.value {
+            None => Lit::Bool(true.into()).into(),
+            Some(JSXAttrValue::Lit(lit)) => lit.into(),
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) => *expr,
+            Some(JSXAttrValue::JSXExprContainer(_)) => *Expr::undefined(DUMMY_SP),
+            Some(JSXAttrValue::JSXElement(el)) => Expr::JSXElement(el),
+            Some(JSXAttrValue::JSXFragment(frag)) => Expr::JSXFragment(frag),
+        })
+    }
+
+    /// Reports the props the element does not understand.
+    fn reject_rest(self, element: &str) {
+        for attr in self.0 {
+            let span = match &attr {
+                JSXAttrOrSpread::JSXAttr(attr) => attr.span,
+                JSXAttrOrSpread::SpreadElement(spread) => spread.dot3_token,
+            };
+            emit_error(span, &format!("unsupported prop on `<{element}>`"));
+        }
+    }
+}
+
+/// The content of `<Show>`: its only element, or a fragment of everything.
+fn children(children: Vec<JSXElementChild>) -> Expr {
+    let mut content = children.into_iter().filter(|child| match child {
+        JSXElementChild::JSXText(text) => jsx_text(&text.value).is_some(),
+        _ => true,
+    });
+    match (content.next(), content.next()) {
+        (Some(JSXElementChild::JSXElement(el)), None) => Expr::JSXElement(el),
+        (first, second) => Expr::JSXFragment(JSXFragment {
+            span: DUMMY_SP,
+            opening: JSXOpeningFragment { span: DUMMY_SP },
+            children: first.into_iter().chain(second).chain(content).collect(),
+            closing: JSXClosingFragment { span: DUMMY_SP },
+        }),
+    }
+}
+
+/// `{(item, index) => ...}`, the single child of `<For>`.
+fn row_fn(children: Vec<JSXElementChild>) -> Option<Expr> {
+    let mut content = children.into_iter().filter(|child| match child {
+        JSXElementChild::JSXText(text) => jsx_text(&text.value).is_some(),
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::JSXEmptyExpr(_),
+            ..
+        }) => false,
+        _ => true,
+    });
+    let Some(JSXElementChild::JSXExprContainer(JSXExprContainer {
+        expr: JSXExpr::Expr(expr),
+        ..
+    })) = content.next()
+    else {
+        return None;
+    };
+    let is_fn = matches!(&*expr, Expr::Arrow(_) | Expr::Fn(_));
+    (is_fn && content.next().is_none()).then_some(*expr)
+}
+
+fn error(span: Span, message: &str) -> Expr {
+    emit_error(span, message);
+    *Expr::undefined(DUMMY_SP)
+}
@@ -0,0 +1,178 @@
+//! Components lifted into custom elements, used from JSX.
+//!
+//! A lifted component reads its props from signals owned by its element, so
+//! it is not called like other components but rendered as that element:
+//!
+//! ```jsx
+//! <Badge count={count} onReset={reset}>New</Badge>
+//! ```
+//!
+//! becomes
+//!
+//! ```js
+//! const _fluxel_badge = document.createElement("fluxel-badge");
+//! effect(() => _fluxel_badge.count = count.value);
+//! _fluxel_badge.addEventListener("reset", reset);
+//! _fluxel_badge.textContent = "New";
+//! ```
+//!
+//! Props are set through the accessors of the element, event handlers listen
+//! to the events it dispatches, and children stay in its light DOM for its
+//! slots to project.
+//!
+//! A component imported from another module may be the class of such an
+//! element, which its module exports in place of the function. Whether it is
+//! only shows at runtime, so imported components are rendered by the
+//! runtime's `createComponent`, which creates elements and calls functions:
+//!
+//! ```js
+//! createComponent(Icon, { name: "close" });
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::ExprFactory;
+use swc_core::ecma::visit::VisitMutWith;
+
+use super::{JsxLowering, append, events, node_ident};
+use crate::component::Component;
+use crate::utils::{assign, call, const_decl, ident, member, str_lit};
+
+/// Components the JSX of a module may render as custom elements.
+#[derive(Clone, Default)]
+pub(crate) struct Elements {
+    /// Tag names of the components lifted in the module.
+    lifted: HashMap<Id, String>,
+    /// Bindings imported from other modules.
+    imported: HashSet<Id>,
+}
+
+impl Elements {
+    pub fn new(m: &Module, components: &[Component]) -> Self {
+        let lifted = components
+            .iter()
+            .map(|component| (component.fn_ident.to_id(), component.tag_name.clone()))
+            .collect();
+        let imported = m
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.type_only => {
+                    Some(&import.specifiers)
+                }
+                _ => None,
+            })
+            .flatten()
+            .map(|specifier| match specifier {
+                ImportSpecifier::Named(named) => named.local.to_id(),
+                ImportSpecifier::Default(default) => default.local.to_id(),
+                ImportSpecifier::Namespace(namespace) => namespace.local.to_id(),
+            })
+            .collect();
+        Self { lifted, imported }
+    }
+}
+
+impl JsxLowering<'_> {
+    /// Tag name of the element `name` was lifted into.
+    pub(super) fn custom_element_tag(&self, name: &JSXElementName) -> Option<String> {
+        let JSXElementName::Ident(name) = name else {
+            return None;
+        };
+        self.elements.lifted.get(&name.to_id()).cloned()
+    }
+
+    /// Whether `name` is imported, e.g. `Icon` or `icons.Close`.
+    pub(super) fn is_imported(&self, name: &JSXElementName) -> bool {
+        fn root(obj: &JSXObject) -> &Ident {
+            match obj {
+                JSXObject::Ident(ident) => ident,
+                JSXObject::JSXMemberExpr(member) => root(&member.obj),
+            }
+        }
+        let ident = match name {
+            JSXElementName::Ident(ident) => ident,
+            JSXElementName::JSXMemberExpr(member) => root(&member.obj),
+            JSXElementName::JSXNamespacedName(_) => return false,
+        };
+        self.elements.imported.contains(&ident.to_id())
+    }
+
+    pub(super) fn custom_element(
+        &mut self,
+        tag: &str,
+        el: JSXElement,
+        stmts: &mut Vec<Stmt>,
+    ) -> Expr {
+        // const _fluxel_badge = document.createElement("fluxel-badge");
+        let node = node_ident(tag);
+        let create = member(ident(self.unresolved, "document"), "createElement");
+        stmts.push(const_decl(node.clone(), call(create, vec![str_lit(tag)])));
+
+        for attr in el.opening.attrs {
+            self.property(&node, attr, stmts);
+        }
+
+        let children = self.children(el.children, stmts, false);
+        match children.as_slice() {
+            [] => {}
+            [ExprOrSpread { spread: None, expr }] if matches!(**expr, Expr::Lit(Lit::Str(_))) => {
+                stmts.push(assign(member(node.clone(), "textContent"), *expr.clone()).into_stmt())
+            }
+            _ => stmts.push(append(node.clone(), children)),
+        }
+
+        node.into()
+    }
+
+    /// `_fluxel_badge.count = 3`, kept up to date when the value reads a
+    /// signal. Event handlers are listened to; names with a dash or a
+    /// namespace are attributes.
+    fn property(&mut self, node: &Ident, attr: JSXAttrOrSpread, stmts: &mut Vec<Stmt>) {
+        let attr = match attr {
+            JSXAttrOrSpread::JSXAttr(attr) => attr,
+            // Object.assign(_fluxel_badge, rest);
+            JSXAttrOrSpread::SpreadElement(mut spread) => {
+                spread.expr.visit_mut_with(self);
+                let assign = member(ident(self.unresolved, "Object"), "assign");
+                stmts.push(call(assign, vec![node.clone().into(), *spread.expr]).into_stmt());
+                return;
+            }
+        };
+
+        let name = match &attr.name {
+            JSXAttrName::Ident(name) if name.sym == "key" => return,
+            JSXAttrName::Ident(name) if name.sym == "class" => "className".to_string(),
+            JSXAttrName::Ident(name) if !name.sym.contains('-') => name.sym.to_string(),
+            // aria-label, data-id, xlink:href, on:item-selected
+            _ => String::new(),
+        };
+        let event = events::event_name(&attr.name);
+        // onclick="..." is an inline handler, as on any element
+        let literal = matches!(attr.value, Some(JSXAttrValue::Lit(_)));
+        if event.is_none() && name.is_empty() || event.is_some() && literal {
+            return self.attr(node, JSXAttrOrSpread::JSXAttr(attr), stmts, false);
+        }
+
+        let value = match attr.value {
+            Some(value) => self.attr_value(value, stmts),
+            // <Toggle checked />
+            None => Expr::Lit(Lit::Bool(true.into())),
+        };
+
+        // onReset={reset} -> addEventListener("reset", reset)
+        if let Some(event) = event {
+            stmts.push(self.listen(node, event, value));
+            return;
+        }
+
+        match self.reactive_read(value) {
+            Ok(read) => {
+                let write = assign(member(node.clone(), &name), read);
+                stmts.push(self.effect(write));
+            }
+            Err(value) => stmts.push(assign(member(node.clone(), &name), value).into_stmt()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Event handler attributes.
+//!
+//! ```jsx
+//! <button onDoubleClick={zoom} on:item-selected={select} />
+//! ```
+//!
+//! listens to `dblclick` and `item-selected`. With
+//! [`Config::delegate_events`](crate::Config::delegate_events), common
+//! bubbling events are stored on the node instead (`_button.$$click = zoom`)
+//! and dispatched by a single listener on the shadow root.
+
+use swc_core::ecma::ast::JSXAttrName;
+
+/// React-style names whose DOM event is not simply the lowercased name.
+const RENAMED_EVENTS: &[(&str, &str)] = &[("DoubleClick", "dblclick")];
+
+/// Bubbling, composed events handled by the shadow root when delegating.
+const DELEGATED_EVENTS: &[&str] = &[
+    "beforeinput",
+    "click",
+    "contextmenu",
+    "dblclick",
+    "focusin",
+    "focusout",
+    "input",
+    "keydown",
+    "keyup",
+    "mousedown",
+    "mousemove",
+    "mouseout",
+    "mouseover",
+    "mouseup",
+    "pointerdown",
+    "pointermove",
+    "pointerout",
+    "pointerover",
+    "pointerup",
+    "touchend",
+    "touchmove",
+    "touchstart",
+];
+
+/// DOM event listened to by an `onX` / `on:x` attribute, `None` for any
+/// other attribute.
+pub(super) fn event_name(name: &JSXAttrName) -> Option<String> {
+    match name {
+        // on:custom-event -> "custom-event", kept verbatim
+        JSXAttrName::JSXNamespacedName(n) if n.ns.sym == "on" => Some(n.name.sym.to_string()),
+        JSXAttrName::JSXNamespacedName(_) => None,
+        JSXAttrName::Ident(name) => {
+            let event = name.sym.strip_prefix("on").filter(|e| !e.is_empty())?;
+            // onclick -> "click"
+            if !event.starts_with(|c: char| c.is_ascii_uppercase()) {
+                return Some(event.to_string());
+            }
+            // onDoubleClick -> "dblclick", onKeyDown -> "keydown"
+            let renamed = RENAMED_EVENTS.iter().find(|(react, _)| *react == event);
+            Some(renamed.map_or_else(|| event.to_ascii_lowercase(), |(_, dom)| dom.to_string()))
+        }
+    }
+}
+
+/// Whether `event` is dispatched by the shadow root in delegated mode.
+pub(super) fn is_delegated(event: &str) -> bool {
+    DELEGATED_EVENTS.contains(&event)
+}
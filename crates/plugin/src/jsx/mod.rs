@@ -0,0 +1,791 @@
+//! Lowers JSX inside a lifted component into direct DOM construction.
+//!
+//! ```jsx
+//! return <p class="count">Count: {count}</p>;
+//! ```
+//!
+//! becomes
+//!
+//! ```js
+//! const _p = document.createElement("p");
+//! _p.className = "count";
+//! const _txt = document.createTextNode("");
+//! effect(() => _txt.data = String(count.value));
+//! _p.append("Count: ", _txt);
+//! return _p;
+//! ```
+//!
+//! Expressions reading a signal or a prop, e.g. `{count.value * 2}`, are
+//! re-evaluated by an `effect`; everything else is written once.
+
+use std::collections::HashSet;
+
+use swc_core::common::util::take::Take;
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::{ExprFactory, private_ident};
+use swc_core::ecma::visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+use crate::config::Codegen;
+use crate::imports::RuntimeImports;
+use crate::utils::{assign, block, call, const_decl, ident, member, not, str_lit};
+
+mod control;
+mod elements;
+mod events;
+mod template;
+
+pub(crate) use elements::Elements;
+
+const SVG_NS: &str = "http://www.w3.org/2000/svg";
+
+/// Attributes that are toggled by presence rather than by value.
+const BOOLEAN_ATTRS: &[&str] = &[
+    "autofocus",
+    "checked",
+    "disabled",
+    "hidden",
+    "inert",
+    "multiple",
+    "open",
+    "readonly",
+    "required",
+    "selected",
+];
+
+/// Rewrites every JSX element and fragment in a component function.
+pub(crate) struct JsxLowering<'a> {
+    imports: &'a mut RuntimeImports,
+    unresolved: SyntaxContext,
+    codegen: Codegen,
+    /// See [`Config::delegate_events`](crate::Config::delegate_events).
+    delegate_events: bool,
+    signals: Signals,
+    elements: Elements,
+    /// Parameter holding the prop signals, see [`crate::props`].
+    props: Option<Id>,
+    /// Module-level statements, i.e. the templates of [`Codegen::Template`].
+    hoisted: Vec<Stmt>,
+    /// Events stored on nodes for the shadow root to dispatch.
+    delegated: Vec<String>,
+}
+
+impl<'a> JsxLowering<'a> {
+    pub fn new(
+        signals: Signals,
+        elements: Elements,
+        props: Option<Ident>,
+        imports: &'a mut RuntimeImports,
+        unresolved: SyntaxContext,
+        codegen: Codegen,
+        delegate_events: bool,
+    ) -> Self {
+        Self {
+            imports,
+            unresolved,
+            codegen,
+            delegate_events,
+            signals,
+            elements,
+            props: props.map(|p| p.to_id()),
+            hoisted: vec![],
+            delegated: vec![],
+        }
+    }
+
+    /// Statements that have to be placed at module scope, ahead of the component.
+    pub fn take_hoisted(&mut self) -> Vec<Stmt> {
+        self.hoisted.take()
+    }
+
+    /// Events the shadow root has to dispatch, in order of first use.
+    pub fn take_delegated(&mut self) -> Vec<String> {
+        self.delegated.take()
+    }
+
+    /// Builds the DOM for `jsx`, returning the construction statements and the
+    /// expression evaluating to the created node.
+    fn build(&mut self, jsx: Expr) -> (Vec<Stmt>, Expr) {
+        let jsx = match self.codegen {
+            Codegen::Dom => jsx,
+            Codegen::Template => match self.build_template(jsx) {
+                Ok(built) => return built,
+                Err(jsx) => jsx,
+            },
+        };
+
+        let mut stmts = vec![];
+        let node = match jsx {
+            Expr::JSXElement(el) => self.element(*el, &mut stmts, false),
+            Expr::JSXFragment(frag) => self.fragment(frag, &mut stmts),
+            Expr::Paren(paren) => return self.build(*paren.expr),
+            _ => unreachable!("build() is only called with JSX"),
+        };
+        (stmts, node)
+    }
+
+    fn element(&mut self, el: JSXElement, stmts: &mut Vec<Stmt>, svg: bool) -> Expr {
+        let tag = match el.opening.name {
+            JSXElementName::Ident(ref i) if is_intrinsic(&i.sym) => i.sym.to_string(),
+            JSXElementName::JSXNamespacedName(ref n) => format!("{}:{}", n.ns.sym, n.name.sym),
+            ref name if let Some(tag) = self.custom_element_tag(name) => {
+                return self.custom_element(&tag, el, stmts);
+            }
+            ref name if let Some(flow) = self.control_flow(name) => {
+                return self.lower_control_flow(flow, el);
+            }
+            name => return self.component(name, el.opening.attrs, el.children, stmts),
+        };
+        let svg = svg || tag == "svg";
+
+        // const _div = document.createElement("div");
+        let node = node_ident(&tag);
+        let document = ident(self.unresolved, "document");
+        let create = if svg {
+            call(
+                member(document, "createElementNS"),
+                vec![str_lit(SVG_NS), str_lit(&tag)],
+            )
+        } else {
+            call(member(document, "createElement"), vec![str_lit(&tag)])
+        };
+        stmts.push(const_decl(node.clone(), create));
+
+        for attr in el.opening.attrs {
+            self.attr(&node, attr, stmts, svg);
+        }
+
+        let children = self.children(el.children, stmts, svg);
+        match children.as_slice() {
+            [] => {}
+            // _button.textContent = "+";
+            [ExprOrSpread { spread: None, expr }] if matches!(**expr, Expr::Lit(Lit::Str(_))) => {
+                stmts.push(assign(member(node.clone(), "textContent"), *expr.clone()).into_stmt())
+            }
+            _ => stmts.push(append(node.clone(), children)),
+        }
+
+        node.into()
+    }
+
+    fn fragment(&mut self, frag: JSXFragment, stmts: &mut Vec<Stmt>) -> Expr {
+        let node = private_ident!("_frag");
+        stmts.push(const_decl(
+            node.clone(),
+            call(
+                member(ident(self.unresolved, "document"), "createDocumentFragment"),
+                vec![],
+            ),
+        ));
+
+        let children = self.children(frag.children, stmts, false);
+        if !children.is_empty() {
+            stmts.push(append(node.clone(), children));
+        }
+
+        node.into()
+    }
+
+    /// `<Counter initial={5}>...</Counter>` becomes `Counter({ initial: 5, children: ... })`
+    /// for components that are not lifted into custom elements, and
+    /// `createComponent(Counter, { ... })` for imported ones.
+    fn component(
+        &mut self,
+        name: JSXElementName,
+        attrs: Vec<JSXAttrOrSpread>,
+        children: Vec<JSXElementChild>,
+        stmts: &mut Vec<Stmt>,
+    ) -> Expr {
+        let mut props = vec![];
+        for attr in attrs {
+            match attr {
+                JSXAttrOrSpread::JSXAttr(attr) => {
+                    let key = match attr.name {
+                        JSXAttrName::Ident(name) => name.sym,
+                        JSXAttrName::JSXNamespacedName(n) => {
+                            format!("{}:{}", n.ns.sym, n.name.sym).into()
+                        }
+                    };
+                    let value = match attr.value {
+                        Some(value) => self.attr_value(value, stmts),
+                        None => Expr::Lit(Lit::Bool(true.into())),
+                    };
+                    props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: prop_name(key),
+                        value: Box::new(value),
+                    }))));
+                }
+                JSXAttrOrSpread::SpreadElement(mut spread) => {
+                    spread.expr.visit_mut_with(self);
+                    props.push(PropOrSpread::Spread(spread));
+                }
+            }
+        }
+
+        let mut children = self.children(children, stmts, false);
+        if !children.is_empty() {
+            let value = match children.as_slice() {
+                [ExprOrSpread { spread: None, .. }] => *children.remove(0).expr,
+                _ => Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: children.into_iter().map(Some).collect(),
+                }),
+            };
+            props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident("children".into()),
+                value: Box::new(value),
+            }))));
+        }
+
+        let props = Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props,
+        });
+        // createComponent(Icon, { ... }) for what may be an element class
+        if self.is_imported(&name) {
+            let create = self.imports.get("createComponent");
+            return call(create, vec![jsx_name_to_expr(name), props]);
+        }
+        call(jsx_name_to_expr(name), vec![props])
+    }
+
+    fn attr(&mut self, node: &Ident, attr: JSXAttrOrSpread, stmts: &mut Vec<Stmt>, svg: bool) {
+        let attr = match attr {
+            JSXAttrOrSpread::JSXAttr(attr) => attr,
+            // Object.assign(_div, rest);
+            JSXAttrOrSpread::SpreadElement(mut spread) => {
+                spread.expr.visit_mut_with(self);
+                let assign = member(ident(self.unresolved, "Object"), "assign");
+                stmts.push(call(assign, vec![node.clone().into(), *spread.expr]).into_stmt());
+                return;
+            }
+        };
+
+        let name = match &attr.name {
+            JSXAttrName::Ident(name) => name.sym.to_string(),
+            JSXAttrName::JSXNamespacedName(n) => format!("{}:{}", n.ns.sym, n.name.sym),
+        };
+        if name == "key" {
+            return;
+        }
+        let event = events::event_name(&attr.name);
+
+        let value = match attr.value {
+            Some(value) => self.attr_value(value, stmts),
+            // <input disabled />
+            None => Expr::Lit(Lit::Bool(true.into())),
+        };
+
+        // onClick={handler} -> addEventListener("click", handler)
+        if let Some(event) = event.filter(|_| !value.is_lit()) {
+            stmts.push(self.listen(node, event, value));
+            return;
+        }
+
+        let (reactive, value) = match self.reactive_read(value) {
+            Ok(read) => (true, read),
+            Err(value) => (false, value),
+        };
+        let write = match (&*name, &value) {
+            ("class" | "className", _) if !svg => assign(member(node.clone(), "className"), value),
+            (_, Expr::Lit(Lit::Bool(Bool { value: true, .. }))) => call(
+                member(node.clone(), "setAttribute"),
+                vec![str_lit(&name), str_lit("")],
+            ),
+            (_, Expr::Lit(Lit::Bool(Bool { value: false, .. }))) => return,
+            (name, _) if BOOLEAN_ATTRS.contains(&name) => call(
+                member(node.clone(), "toggleAttribute"),
+                vec![str_lit(name), not(not(value))],
+            ),
+            (name, _) => {
+                let name = if name == "className" { "class" } else { name };
+                // literal text is set as is; other values may remove the attribute
+                if matches!(value, Expr::Lit(Lit::Str(_) | Lit::Num(_)) | Expr::Tpl(_)) {
+                    call(
+                        member(node.clone(), "setAttribute"),
+                        vec![str_lit(name), value],
+                    )
+                } else {
+                    call(
+                        self.imports.get("setAttr"),
+                        vec![node.clone().into(), str_lit(name), value],
+                    )
+                }
+            }
+        };
+
+        stmts.push(if reactive {
+            self.effect(write)
+        } else {
+            write.into_stmt()
+        });
+    }
+
+    /// `_button.addEventListener("click", handler)`, or `_button.$$click = handler`
+    /// when the event is delegated to the shadow root.
+    fn listen(&mut self, node: &Ident, event: String, handler: Expr) -> Stmt {
+        if self.delegate_events && events::is_delegated(&event) {
+            let write = assign(member(node.clone(), &format!("$${event}")), handler);
+            if !self.delegated.contains(&event) {
+                self.delegated.push(event);
+            }
+            return write.into_stmt();
+        }
+        let listen = member(node.clone(), "addEventListener");
+        call(listen, vec![str_lit(&event), handler]).into_stmt()
+    }
+
+    fn attr_value(&mut self, value: JSXAttrValue, stmts: &mut Vec<Stmt>) -> Expr {
+        match value {
+            JSXAttrValue::Lit(lit) => Expr::Lit(lit),
+            JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(mut expr),
+                ..
+            }) => {
+                expr.visit_mut_with(self);
+                *expr
+            }
+            JSXAttrValue::JSXExprContainer(_) => *Expr::undefined(DUMMY_SP),
+            JSXAttrValue::JSXElement(el) => self.element(*el, stmts, false),
+            JSXAttrValue::JSXFragment(frag) => self.fragment(frag, stmts),
+        }
+    }
+
+    /// Lowers children into the argument list of `append`.
+    fn children(
+        &mut self,
+        children: Vec<JSXElementChild>,
+        stmts: &mut Vec<Stmt>,
+        svg: bool,
+    ) -> Vec<ExprOrSpread> {
+        let mut args = vec![];
+        for child in children {
+            match child {
+                JSXElementChild::JSXText(text) => {
+                    if let Some(text) = jsx_text(&text.value) {
+                        args.push(str_lit(&text).as_arg());
+                    }
+                }
+                JSXElementChild::JSXExprContainer(JSXExprContainer { expr, .. }) => {
+                    let JSXExpr::Expr(expr) = expr else {
+                        continue; // {/* comment */}
+                    };
+                    args.extend(self.dynamic_child(*expr, stmts));
+                }
+                // {...items}
+                JSXElementChild::JSXSpreadChild(JSXSpreadChild { mut expr, .. }) => {
+                    expr.visit_mut_with(self);
+                    args.push(ExprOrSpread {
+                        spread: Some(DUMMY_SP),
+                        expr,
+                    });
+                }
+                JSXElementChild::JSXElement(el) => {
+                    args.push(self.element(*el, stmts, svg).as_arg())
+                }
+                JSXElementChild::JSXFragment(frag) => {
+                    args.extend(self.children(frag.children, stmts, svg));
+                }
+            }
+        }
+        args
+    }
+
+    /// `{expr}` in child position: `.map()` over a signal becomes a keyed
+    /// list, `null`, `undefined` and booleans nothing. Expressions reading a
+    /// signal become a text node kept up to date by an effect when they
+    /// always yield a string or a number, and are inserted by `insert`
+    /// otherwise, which renders nodes and skips the values rendering nothing.
+    /// Other strings and numbers are appended as they are, any other value
+    /// once through `toChildren`.
+    fn dynamic_child(&mut self, expr: Expr, stmts: &mut Vec<Stmt>) -> Option<ExprOrSpread> {
+        // the `key` of the mapped element is read before the JSX is lowered
+        let mut expr = match self.keyed_list(expr) {
+            Ok(list) => return Some(list.as_arg()),
+            Err(expr) => expr,
+        };
+        expr.visit_mut_with(self);
+
+        let child = match expr {
+            expr if renders_nothing(&expr) => return None,
+            Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => expr.as_arg(),
+            Expr::Lit(Lit::Num(num)) => str_lit(&num.value.to_string()).as_arg(),
+            // {items.map(...)} yields an array of nodes
+            expr if is_map_call(&expr) => ExprOrSpread {
+                spread: Some(DUMMY_SP),
+                expr: Box::new(expr),
+            },
+            expr => match self.reactive_read(expr) {
+                Ok(read) if is_text(&read, &self.signals.text, self.unresolved) => {
+                    // const _txt = document.createTextNode("");
+                    // effect(() => _txt.data = String(count.value));
+                    let text = private_ident!("_txt");
+                    let create = member(ident(self.unresolved, "document"), "createTextNode");
+                    stmts.push(const_decl(text.clone(), call(create, vec![str_lit("")])));
+
+                    let value = call(ident(self.unresolved, "String"), vec![read]);
+                    stmts.push(self.effect(assign(member(text.clone(), "data"), value)));
+                    text.as_arg()
+                }
+                // insert(() => open.value ? _b : "closed")
+                Ok(read) => {
+                    let read = Box::new(read).into_lazy_arrow(vec![]);
+                    call(self.imports.get("insert"), vec![read.into()]).as_arg()
+                }
+                // ...toChildren(label)
+                Err(expr) => ExprOrSpread {
+                    spread: Some(DUMMY_SP),
+                    expr: Box::new(call(self.imports.get("toChildren"), vec![expr])),
+                },
+            },
+        };
+        Some(child)
+    }
+
+    /// Turns `expr` into the expression an effect re-evaluates, or hands it
+    /// back when it does not depend on any signal.
+    fn reactive_read(&self, expr: Expr) -> Result<Expr, Expr> {
+        match expr {
+            // {count} -> count.value
+            Expr::Ident(i) if self.signals.bindings.contains(&i.to_id()) => Ok(member(i, "value")),
+            // {count.value * 2}, {user.value.name}, {__props.label.value}
+            expr if self.is_reactive(&expr) => Ok(expr),
+            expr => Err(expr),
+        }
+    }
+
+    /// Whether evaluating `expr` reads a signal or a prop. Nested functions
+    /// are skipped: they run later, outside of the effect.
+    fn is_reactive(&self, expr: &Expr) -> bool {
+        reads(expr, |id| {
+            self.signals.bindings.contains(id) || self.props.as_ref() == Some(id)
+        })
+    }
+
+    /// `effect(() => <body>);`
+    fn effect(&mut self, body: Expr) -> Stmt {
+        let effect = self.imports.get("effect");
+        call(effect, vec![Box::new(body).into_lazy_arrow(vec![]).into()]).into_stmt()
+    }
+}
+
+impl VisitMut for JsxLowering<'_> {
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        let mut out = Vec::with_capacity(stmts.len());
+
+        for mut stmt in stmts.take() {
+            match &mut stmt {
+                // return <div />;
+                Stmt::Return(ReturnStmt { arg: Some(arg), .. }) if is_jsx(arg) => {
+                    let (built, node) = self.build(*arg.take());
+                    out.extend(built);
+                    **arg = node;
+                }
+                // const view = <div />;
+                Stmt::Decl(Decl::Var(var))
+                    if var.decls.len() == 1 && var.decls[0].init.as_deref().is_some_and(is_jsx) =>
+                {
+                    let init = var.decls[0].init.as_mut().unwrap();
+                    let (built, node) = self.build(*init.take());
+                    out.extend(built);
+                    **init = node;
+                }
+                _ => stmt.visit_mut_with(self),
+            }
+            out.push(stmt);
+        }
+
+        *stmts = out;
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        // (item) => <li /> is lowered as (item) => { ...; return _li; }
+        if let BlockStmtOrExpr::Expr(body) = &mut *arrow.body
+            && is_jsx(body)
+        {
+            let body = body.take();
+            *arrow.body = BlockStmtOrExpr::BlockStmt(block(vec![body.into_return_stmt().into()]));
+        }
+        arrow.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if !is_jsx(expr) {
+            expr.visit_mut_children_with(self);
+            return;
+        }
+
+        // JSX in any other expression position: (() => { ...; return _div; })()
+        let (mut stmts, node) = self.build(expr.take());
+        stmts.push(Box::new(node).into_return_stmt().into());
+        *expr = ArrowExpr {
+            body: Box::new(BlockStmtOrExpr::BlockStmt(block(stmts))),
+            ..Default::default()
+        }
+        .as_iife()
+        .into();
+    }
+}
+
+/// Collects the bindings initialized by `signal()` / `computed()`.
+pub(crate) fn collect_signals(
+    m: &Module,
+    imports: &RuntimeImports,
+    unresolved: SyntaxContext,
+) -> Signals {
+    struct Collector {
+        unresolved: SyntaxContext,
+        signal: Option<Id>,
+        computed: Option<Id>,
+        signals: Signals,
+    }
+
+    impl Visit for Collector {
+        fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
+            decl.visit_children_with(self);
+
+            let (Pat::Ident(name), Some(init)) = (&decl.name, &decl.init) else {
+                return;
+            };
+            let Expr::Call(CallExpr {
+                callee: Callee::Expr(callee),
+                args,
+                type_args,
+                ..
+            }) = &**init
+            else {
+                return;
+            };
+            let Some(callee) = callee.as_ident().map(Ident::to_id) else {
+                return;
+            };
+            let value = match args.as_slice() {
+                [ExprOrSpread { spread: None, expr }] => Some(&**expr),
+                _ => None,
+            };
+            let text = if Some(&callee) == self.signal.as_ref() {
+                // signal(0), but not signal<number | null>(0)
+                type_args.is_none() && value.is_some_and(is_text_lit)
+            } else if Some(&callee) == self.computed.as_ref() {
+                // computed(() => count.value * 2)
+                matches!(value, Some(Expr::Arrow(ArrowExpr { body, .. }))
+                    if matches!(&**body, BlockStmtOrExpr::Expr(body)
+                        if is_text(body, &self.signals.text, self.unresolved)))
+            } else {
+                return;
+            };
+            self.signals.bindings.insert(name.to_id());
+            if text {
+                self.signals.text.insert(name.to_id());
+            }
+        }
+    }
+
+    let mut collector = Collector {
+        unresolved,
+        signal: imports.find("signal").map(Ident::to_id),
+        computed: imports.find("computed").map(Ident::to_id),
+        signals: Signals::default(),
+    };
+    m.visit_with(&mut collector);
+    collector.signals
+}
+
+/// Bindings created by `signal()` / `computed()` in the module.
+#[derive(Clone, Default)]
+pub(crate) struct Signals {
+    bindings: HashSet<Id>,
+    /// Signals always holding a string or a number, e.g. `signal(0)`.
+    text: HashSet<Id>,
+}
+
+/// `"a"`, `1`, `-1` or a template literal.
+fn is_text_lit(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Str(_) | Lit::Num(_)) | Expr::Tpl(_) => true,
+        Expr::Unary(UnaryExpr {
+            op: op!(unary, "-"),
+            arg,
+            ..
+        }) => matches!(&**arg, Expr::Lit(Lit::Num(_))),
+        _ => false,
+    }
+}
+
+/// Whether `expr` always yields a string or a number, so that it can be
+/// rendered as text: literals, arithmetic, `typeof`, `String(x)` and reads of
+/// the `text` signals.
+fn is_text(expr: &Expr, text: &HashSet<Id>, unresolved: SyntaxContext) -> bool {
+    let is_text = |expr: &Expr| is_text(expr, text, unresolved);
+    match expr {
+        expr if is_text_lit(expr) => true,
+        Expr::Paren(ParenExpr { expr, .. }) => is_text(expr),
+        Expr::Bin(bin) => !matches!(
+            bin.op,
+            op!("==")
+                | op!("!=")
+                | op!("===")
+                | op!("!==")
+                | op!("<")
+                | op!("<=")
+                | op!(">")
+                | op!(">=")
+                | op!("&&")
+                | op!("||")
+                | op!("??")
+                | op!("in")
+                | op!("instanceof")
+        ),
+        Expr::Unary(UnaryExpr { op, .. }) => matches!(
+            op,
+            op!(unary, "-") | op!(unary, "+") | op!("~") | op!("typeof")
+        ),
+        Expr::Update(_) => true,
+        Expr::Cond(cond) => is_text(&cond.cons) && is_text(&cond.alt),
+        Expr::Seq(seq) => seq.exprs.last().is_some_and(|expr| is_text(expr)),
+        // count.value
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(prop),
+            ..
+        }) if prop.sym == "value" => {
+            matches!(&**obj, Expr::Ident(i) if text.contains(&i.to_id()))
+        }
+        // String(x)
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            ..
+        }) => matches!(&**callee, Expr::Ident(i) if i.sym == "String" && i.ctxt == unresolved),
+        _ => false,
+    }
+}
+
+/// `_div` for a `<div>`
+fn node_ident(tag: &str) -> Ident {
+    private_ident!(format!(
+        "_{}",
+        tag.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+    ))
+}
+
+/// Whether `expr` reads a binding accepted by `binding` when evaluated,
+/// ignoring nested functions.
+fn reads(expr: &Expr, binding: impl Fn(&Id) -> bool) -> bool {
+    struct Reads<F> {
+        binding: F,
+        found: bool,
+    }
+
+    impl<F: Fn(&Id) -> bool> Visit for Reads<F> {
+        fn visit_ident(&mut self, i: &Ident) {
+            self.found |= (self.binding)(&i.to_id());
+        }
+
+        fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+        fn visit_function(&mut self, _: &Function) {}
+    }
+
+    let mut visitor = Reads {
+        binding,
+        found: false,
+    };
+    expr.visit_with(&mut visitor);
+    visitor.found
+}
+
+fn is_jsx(expr: &Expr) -> bool {
+    match expr {
+        Expr::JSXElement(_) | Expr::JSXFragment(_) => true,
+        Expr::Paren(paren) => is_jsx(&paren.expr),
+        _ => false,
+    }
+}
+
+/// `{null}`, `{undefined}` and `{false}`, which JSX renders as nothing.
+fn renders_nothing(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Null(_) | Lit::Bool(_)) => true,
+        Expr::Ident(i) => i.sym == "undefined",
+        _ => false,
+    }
+}
+
+/// `xs.map(...)`
+fn is_map_call(expr: &Expr) -> bool {
+    let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        ..
+    }) = expr
+    else {
+        return false;
+    };
+    matches!(&**callee, Expr::Member(MemberExpr { prop: MemberProp::Ident(prop), .. }) if prop.sym == "map")
+}
+
+/// Lowercase names and names containing a dash are DOM elements, everything
+/// else refers to a component in scope.
+fn is_intrinsic(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase()) || name.contains('-')
+}
+
+fn jsx_name_to_expr(name: JSXElementName) -> Expr {
+    fn object(obj: JSXObject) -> Expr {
+        match obj {
+            JSXObject::Ident(i) => i.into(),
+            JSXObject::JSXMemberExpr(m) => member(object(m.obj), &m.prop.sym),
+        }
+    }
+
+    match name {
+        JSXElementName::Ident(i) => i.into(),
+        JSXElementName::JSXMemberExpr(m) => member(object(m.obj), &m.prop.sym),
+        JSXElementName::JSXNamespacedName(_) => unreachable!("namespaced names are intrinsic"),
+    }
+}
+
+fn prop_name(key: swc_core::ecma::atoms::Atom) -> PropName {
+    if Ident::verify_symbol(&key).is_ok() {
+        PropName::Ident(key.into())
+    } else {
+        PropName::Str(key.into())
+    }
+}
+
+/// `node.append(children...);`
+fn append(node: Ident, children: Vec<ExprOrSpread>) -> Stmt {
+    Box::new(member(node, "append"))
+        .as_call(DUMMY_SP, children)
+        .into_stmt()
+}
+
+/// Applies the JSX whitespace rules: lines are trimmed, blank lines are
+/// dropped and the remaining lines are joined by a single space.
+fn jsx_text(value: &str) -> Option<String> {
+    let lines: Vec<&str> = value.lines().collect();
+    let last_non_empty = lines
+        .iter()
+        .rposition(|line| line.contains(|c| c != ' ' && c != '\t'))
+        // `{a} {b}`: a lone whitespace line is kept as is
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut line = line.replace('\t', " ");
+        if i != 0 {
+            line = line.trim_start_matches(' ').to_string();
+        }
+        if i != lines.len() - 1 {
+            line = line.trim_end_matches(' ').to_string();
+        }
+        if line.is_empty() {
+            continue;
+        }
+        text.push_str(&line);
+        if i != last_non_empty {
+            text.push(' ');
+        }
+    }
+
+    (!text.is_empty()).then_some(text)
+}
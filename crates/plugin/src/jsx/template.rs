@@ -0,0 +1,427 @@
+//! Template-cloning code generation.
+//!
+//! The static skeleton of a JSX tree is serialized to HTML and parsed once
+//! into a module-level `<template>`:
+//!
+//! ```js
+//! const _tmpl = document.createElement("template");
+//! _tmpl.innerHTML = "<p>Count: <!></p>";
+//! ```
+//!
+//! Each instance clones it and walks to the dynamic holes only:
+//!
+//! ```js
+//! const _p = _tmpl.content.firstChild.cloneNode(true);
+//! const _marker = _p.firstChild.nextSibling;
+//! _marker.replaceWith(_txt);
+//! ```
+//!
+//! The walks follow the JSX, so trees the HTML parser would restructure, such
+//! as a `<tr>` it wraps into an implied `<tbody>`, are built with DOM calls.
+
+use std::fmt::Write;
+
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::{ExprFactory, private_ident};
+use swc_core::ecma::visit::VisitMutWith;
+
+use super::{JsxLowering, is_intrinsic, jsx_text, renders_nothing};
+use crate::utils::{assign, call, const_decl, ident, member, str_lit};
+
+/// Elements that have no closing tag in HTML.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements the HTML parser moves or wraps depending on their parent, e.g. the
+/// `<tbody>` it inserts between `<table>` and `<tr>`.
+const CONTEXT_SENSITIVE: &[&str] = &[
+    "caption", "col", "colgroup", "optgroup", "option", "select", "table", "tbody", "td", "tfoot",
+    "th", "thead", "tr",
+];
+
+/// Elements closing an open `<p>`.
+const CLOSES_P: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "ul",
+];
+
+/// Elements closing an open element of the same kind.
+const NOT_NESTED: &[&str] = &["a", "button", "form"];
+
+/// Headings, which close a heading they are the direct child of.
+const HEADINGS: &[&str] = &["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Statements produced while instantiating a template.
+#[derive(Default)]
+struct Instance {
+    /// `const _x = _parent.firstChild...;` — run before the DOM is mutated.
+    walks: Vec<Stmt>,
+    /// Attributes, listeners and hole replacements.
+    bindings: Vec<Stmt>,
+}
+
+impl JsxLowering<'_> {
+    /// Template counterpart of [`JsxLowering::build`]. Hands the JSX back when
+    /// the root is a component, which has no static markup to hoist.
+    pub(super) fn build_template(&mut self, jsx: Expr) -> Result<(Vec<Stmt>, Expr), Expr> {
+        let mut html = String::new();
+        let mut instance = Instance::default();
+        let tmpl = private_ident!("_tmpl");
+        let content = member(tmpl.clone(), "content");
+
+        let root = match jsx {
+            Expr::JSXElement(el) if is_static_root(&el) && parses_back(&el, &mut vec![]) => {
+                let node = node_ident(&el);
+                self.template_element(*el, &node, false, &mut html, &mut instance);
+                // const _div = _tmpl.content.firstChild.cloneNode(true);
+                let clone = call(
+                    member(member(content, "firstChild"), "cloneNode"),
+                    vec![Lit::Bool(true.into()).into()],
+                );
+                (node, clone)
+            }
+            Expr::JSXFragment(frag) if children_parse_back(&frag.children, &mut vec![]) => {
+                let node = private_ident!("_frag");
+                self.template_children(frag.children, &node, false, &mut html, &mut instance);
+                // const _frag = _tmpl.content.cloneNode(true);
+                let clone = call(
+                    member(content, "cloneNode"),
+                    vec![Lit::Bool(true.into()).into()],
+                );
+                (node, clone)
+            }
+            Expr::Paren(paren) => return self.build_template(*paren.expr),
+            jsx => return Err(jsx),
+        };
+
+        // const _tmpl = document.createElement("template");
+        // _tmpl.innerHTML = "...";
+        let create = call(
+            member(ident(self.unresolved, "document"), "createElement"),
+            vec![str_lit("template")],
+        );
+        self.hoisted.push(const_decl(tmpl.clone(), create));
+        self.hoisted
+            .push(assign(member(tmpl, "innerHTML"), str_lit(&html)).into_stmt());
+
+        let (node, clone) = root;
+        let mut stmts = vec![const_decl(node.clone(), clone)];
+        stmts.extend(instance.walks);
+        stmts.extend(instance.bindings);
+        Ok((stmts, node.into()))
+    }
+
+    /// Serializes `el` into `html`; `node` refers to the cloned element.
+    fn template_element(
+        &mut self,
+        el: JSXElement,
+        node: &Ident,
+        svg: bool,
+        html: &mut String,
+        instance: &mut Instance,
+    ) {
+        let JSXElementName::Ident(tag) = &el.opening.name else {
+            unreachable!("only intrinsic elements are serialized");
+        };
+        let tag = tag.sym.clone();
+        let svg = svg || tag == "svg";
+
+        html.push('<');
+        html.push_str(&tag);
+        for attr in el.opening.attrs {
+            match static_attr(&attr) {
+                Some((name, None)) => write!(html, " {name}").unwrap(),
+                Some((name, Some(value))) => {
+                    write!(html, " {name}=\"{}\"", escape(&value, true)).unwrap()
+                }
+                None => self.attr(node, attr, &mut instance.bindings, svg),
+            }
+        }
+        html.push('>');
+
+        if VOID_ELEMENTS.contains(&&*tag) {
+            return;
+        }
+
+        self.template_children(el.children, node, svg, html, instance);
+        write!(html, "</{tag}>").unwrap();
+    }
+
+    fn template_children(
+        &mut self,
+        children: Vec<JSXElementChild>,
+        parent: &Ident,
+        svg: bool,
+        html: &mut String,
+        instance: &mut Instance,
+    ) {
+        // DOM index of the next child, and the last sibling we hold a reference to
+        let mut index = 0;
+        let mut prev: Option<(Ident, usize)> = None;
+        let mut in_text = false;
+
+        let mut walk = |instance: &mut Instance, name: Ident, index: usize| {
+            let path = match &prev {
+                Some((sibling, at)) => siblings(sibling.clone().into(), index - at),
+                None => siblings(member(parent.clone(), "firstChild"), index),
+            };
+            instance.walks.push(const_decl(name.clone(), path));
+            prev = Some((name, index));
+        };
+
+        for child in flatten(children) {
+            match child {
+                JSXElementChild::JSXText(text) => {
+                    if let Some(text) = jsx_text(&text.value) {
+                        html.push_str(&escape(&text, false));
+                        if !in_text {
+                            in_text = true;
+                            index += 1;
+                        }
+                    }
+                    continue;
+                }
+                JSXElementChild::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                }) if is_static_text(&expr) => {
+                    html.push_str(&escape(&static_text(&expr), false));
+                    if !in_text {
+                        in_text = true;
+                        index += 1;
+                    }
+                    continue;
+                }
+                JSXElementChild::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::JSXEmptyExpr(_),
+                    ..
+                }) => continue,
+                JSXElementChild::JSXElement(el) if is_static_root(&el) => {
+                    let node = node_ident(&el);
+                    if needs_ref(&el) {
+                        walk(instance, node.clone(), index);
+                    }
+                    self.template_element(*el, &node, svg, html, instance);
+                }
+                child => {
+                    // <!> marks a hole filled at runtime
+                    html.push_str("<!>");
+                    let marker = private_ident!("_marker");
+                    walk(instance, marker.clone(), index);
+                    self.fill_hole(child, marker, svg, instance);
+                }
+            }
+            in_text = false;
+            index += 1;
+        }
+    }
+
+    /// `_marker.replaceWith(<node>);`
+    fn fill_hole(
+        &mut self,
+        child: JSXElementChild,
+        marker: Ident,
+        svg: bool,
+        instance: &mut Instance,
+    ) {
+        let stmts = &mut instance.bindings;
+        let arg = match child {
+            JSXElementChild::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            }) => self
+                .dynamic_child(*expr, stmts)
+                .expect("children rendering nothing have no hole"),
+            JSXElementChild::JSXSpreadChild(JSXSpreadChild { mut expr, .. }) => {
+                expr.visit_mut_with(self);
+                ExprOrSpread {
+                    spread: Some(DUMMY_SP),
+                    expr,
+                }
+            }
+            JSXElementChild::JSXElement(el) => self.element(*el, stmts, svg).as_arg(),
+            _ => unreachable!("static children are serialized"),
+        };
+
+        let replace = Box::new(member(marker, "replaceWith"));
+        stmts.push(replace.as_call(DUMMY_SP, vec![arg]).into_stmt());
+    }
+}
+
+/// Inlines the children of nested fragments and drops the ones rendering
+/// nothing, e.g. `{null}`.
+fn flatten(children: Vec<JSXElementChild>) -> Vec<JSXElementChild> {
+    children
+        .into_iter()
+        .flat_map(|child| match child {
+            JSXElementChild::JSXFragment(frag) => flatten(frag.children),
+            JSXElementChild::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            }) if renders_nothing(&expr) => vec![],
+            child => vec![child],
+        })
+        .collect()
+}
+
+/// Whether the HTML parser builds the static elements of `el` into the same
+/// tree as the JSX, inside the elements `open`.
+fn parses_back(el: &JSXElement, open: &mut Vec<String>) -> bool {
+    let JSXElementName::Ident(tag) = &el.opening.name else {
+        unreachable!("only intrinsic elements are serialized");
+    };
+    let tag = tag.sym.to_string();
+    let parent = open.last().map(String::as_str);
+    let closes = |ancestor: &str| {
+        NOT_NESTED.contains(&ancestor) && ancestor == tag
+            || ancestor == "p" && CLOSES_P.contains(&&*tag)
+    };
+    let reparsed = CONTEXT_SENSITIVE.contains(&&*tag)
+        || open.iter().any(|ancestor| closes(ancestor))
+        || HEADINGS.contains(&&*tag) && parent.is_some_and(|parent| HEADINGS.contains(&parent))
+        || list_item_closes(&tag, open);
+    if reparsed {
+        return false;
+    }
+
+    open.push(tag);
+    let parses_back = children_parse_back(&el.children, open);
+    open.pop();
+    parses_back
+}
+
+fn children_parse_back(children: &[JSXElementChild], open: &mut Vec<String>) -> bool {
+    flatten(children.to_vec()).iter().all(|child| match child {
+        JSXElementChild::JSXElement(el) if is_static_root(el) => parses_back(el, open),
+        _ => true,
+    })
+}
+
+/// Whether `<li>`, `<dd>` or `<dt>` closes an open item of its list.
+fn list_item_closes(tag: &str, open: &[String]) -> bool {
+    let (items, lists): (&[&str], &[&str]) = match tag {
+        "li" => (&["li"], &["ul", "ol", "menu"]),
+        "dd" | "dt" => (&["dd", "dt"], &["dl"]),
+        _ => return false,
+    };
+    open.iter()
+        .rev()
+        .take_while(|ancestor| !lists.contains(&ancestor.as_str()))
+        .any(|ancestor| items.contains(&ancestor.as_str()))
+}
+
+fn is_static_root(el: &JSXElement) -> bool {
+    matches!(&el.opening.name, JSXElementName::Ident(i) if is_intrinsic(&i.sym))
+}
+
+/// Whether the cloned element has to be referenced at runtime, either for
+/// its own bindings or to reach a hole below it.
+fn needs_ref(el: &JSXElement) -> bool {
+    el.opening
+        .attrs
+        .iter()
+        .any(|attr| static_attr(attr).is_none())
+        || flatten(el.children.clone())
+            .iter()
+            .any(|child| match child {
+                JSXElementChild::JSXText(_) => false,
+                JSXElementChild::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::JSXEmptyExpr(_),
+                    ..
+                }) => false,
+                JSXElementChild::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                }) => !is_static_text(expr),
+                JSXElementChild::JSXElement(el) => !is_static_root(el) || needs_ref(el),
+                _ => true,
+            })
+}
+
+/// Name and value of an attribute that can be serialized into the template.
+fn static_attr(attr: &JSXAttrOrSpread) -> Option<(String, Option<String>)> {
+    let JSXAttrOrSpread::JSXAttr(attr) = attr else {
+        return None;
+    };
+    let name = match &attr.name {
+        JSXAttrName::Ident(name) if name.sym == "className" => "class".to_string(),
+        JSXAttrName::Ident(name) if name.sym == "key" => return None,
+        JSXAttrName::Ident(name) => name.sym.to_string(),
+        JSXAttrName::JSXNamespacedName(n) => format!("{}:{}", n.ns.sym, n.name.sym),
+    };
+    match &attr.value {
+        None => Some((name, None)),
+        Some(JSXAttrValue::Lit(Lit::Str(value))) => Some((name, Some(value.value.to_string()))),
+        _ => None,
+    }
+}
+
+fn is_static_text(expr: &Expr) -> bool {
+    matches!(expr, Expr::Lit(Lit::Str(_) | Lit::Num(_)))
+}
+
+fn static_text(expr: &Expr) -> String {
+    match expr {
+        Expr::Lit(Lit::Str(s)) => s.value.to_string(),
+        Expr::Lit(Lit::Num(n)) => n.value.to_string(),
+        _ => unreachable!(),
+    }
+}
+
+fn node_ident(el: &JSXElement) -> Ident {
+    let JSXElementName::Ident(tag) = &el.opening.name else {
+        unreachable!("only intrinsic elements are serialized");
+    };
+    super::node_ident(&tag.sym)
+}
+
+/// `node.nextSibling.nextSibling...`
+fn siblings(node: Expr, count: usize) -> Expr {
+    (0..count).fold(node, |node, _| member(node, "nextSibling"))
+}
+
+fn escape(text: &str, attr: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' if !attr => out.push_str("&lt;"),
+            '>' if !attr => out.push_str("&gt;"),
+            '"' if attr => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -1,32 +1,33 @@
-use swc_core::plugin::{plugin_transform, proxies::TransformPluginProgramMetadata};
-use swc_core::{
-    common::Spanned,
-    ecma::{
-        ast::{BinExpr, Ident, Module, Program, op},
-        transforms::testing::test_inline,
-        visit::{FoldWith, VisitMut, VisitMutWith, visit_mut_pass},
-    },
-};
-
-pub struct FluxelTransform;
+//! Fluxel SWC transform – lifts TSX component functions into Web Component classes.
+//! Build with: `cargo build-wasip1 --release`
 
-impl VisitMut for FluxelTransform {
-    // Implement necessary visit_mut_* methods for actual custom transform.
-    // A comprehensive list of possible visitor methods can be found here:
-    // https://rustdoc.swc.rs/swc_ecma_visit/trait.VisitMut.html
+use swc_core::ecma::{ast::Program, visit::VisitMutWith};
+use swc_core::plugin::metadata::TransformPluginMetadataContextKind;
+use swc_core::plugin::{plugin_transform, proxies::TransformPluginProgramMetadata};
 
-    fn visit_mut_module(&mut self, node: &mut Module) {}
+mod component;
+mod config;
+mod css;
+mod discover;
+mod element;
+mod emit;
+mod imports;
+mod jsdoc;
+mod jsx;
+mod lifecycle;
+pub mod manifest;
+mod options;
+mod props;
+mod slots;
+mod styles;
+mod transform;
+mod types;
+mod utils;
 
-    fn visit_mut_bin_expr(&mut self, e: &mut BinExpr) {
-        e.visit_mut_children_with(self);
+pub use config::{Codegen, Config, ShadowMode};
+pub use transform::FluxelTransform;
 
-        if e.op == op!("===") {
-            e.left = Box::new(Ident::new_no_ctxt("kdy1".into(), e.left.span()).into());
-        }
-    }
-}
-
-/// An example plugin function with macro support.
+/// SWC plugin entry point.
 /// `plugin_transform` macro interop pointers into deserialized structs, as well
 /// as returning ptr back to host.
 ///
@@ -44,8 +45,19 @@ impl VisitMut for FluxelTransform {
 #[plugin_transform]
 pub fn process_transform(
     mut program: Program,
-    _metadata: TransformPluginProgramMetadata,
+    metadata: TransformPluginProgramMetadata,
 ) -> Program {
-    program.visit_mut_with(&mut FluxelTransform);
+    let config = match metadata.get_transform_plugin_config() {
+        Some(json) => Config::from_json(&json)
+            .unwrap_or_else(|err| panic!("invalid @fluxel/swc-plugin config: {err}")),
+        None => Config::default(),
+    };
+
+    let filename = metadata.get_context(&TransformPluginMetadataContextKind::Filename);
+
+    program.visit_mut_with(
+        &mut FluxelTransform::new(config, metadata.unresolved_mark, metadata.comments)
+            .with_filename(filename),
+    );
     program
 }
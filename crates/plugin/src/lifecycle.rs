@@ -0,0 +1,178 @@
+//! `onMount` / `onCleanup` and the disposal of effects.
+//!
+//! ```tsx
+//! export default function Clock() {
+//!   const now = signal(new Date());
+//!   onMount(() => {
+//!     const timer = setInterval(() => (now.value = new Date()), 1000);
+//!     return () => clearInterval(timer);
+//!   });
+//!   return <p>{now.value.toLocaleTimeString()}</p>;
+//! }
+//! ```
+//!
+//! Elements rendering effects or registering hooks render through their
+//! lifecycle, which collects both, and drive it from the custom element
+//! callbacks:
+//!
+//! ```js
+//! #lifecycle = createLifecycle();
+//! connectedCallback() {
+//!   // first connection: const _n = render(this.#lifecycle, () => Clock(this.#props)); ...
+//!   connect(this.#lifecycle);
+//! }
+//! disconnectedCallback() { disconnect(this.#lifecycle, this); }
+//! ```
+//!
+//! Once the element has left the document, its cleanups run and its effects
+//! are disposed.
+//!
+//! Components calling `useFormValue(value)` (or `useInternals`,
+//! `onFormReset`, `onFormStateRestore`), or tagged `@formAssociated`, take
+//! part in forms:
+//!
+//! ```js
+//! static formAssociated = true;
+//! #lifecycle = createLifecycle(this.attachInternals());
+//! formResetCallback() { resetForm(this.#lifecycle); }
+//! formStateRestoreCallback(state, mode) { restoreForm(this.#lifecycle, state, mode); }
+//! ```
+//!
+//! `useFormValue` submits the value of the signal with the form and returns
+//! the `ElementInternals`, e.g. for `setValidity`.
+
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::imports::RuntimeImports;
+use crate::utils::emit_error;
+
+/// Hooks registered by a component.
+const HOOKS: &[&str] = &[
+    "onMount",
+    "onCleanup",
+    "useFormValue",
+    "useInternals",
+    "onFormReset",
+    "onFormStateRestore",
+];
+
+/// Hooks making the element form-associated.
+const FORM_HOOKS: &[&str] = &[
+    "useFormValue",
+    "useInternals",
+    "onFormReset",
+    "onFormStateRestore",
+];
+
+/// Runtime helpers creating effects.
+const EFFECTS: &[&str] = &["effect", "computed", "createShow", "createFor", "insert"];
+
+/// What the element of a compiled component function has to provide.
+#[derive(Debug, Default)]
+pub(crate) struct Needs {
+    /// It registers hooks or creates effects.
+    pub lifecycle: bool,
+    /// It calls form hooks.
+    pub form_associated: bool,
+}
+
+/// Inspects the compiled `function`. Reports hooks called anywhere but at
+/// its top level, where they would run after the render and never be
+/// registered.
+pub(crate) fn needs(function: &Function, imports: &RuntimeImports) -> Needs {
+    let Some(body) = &function.body else {
+        return Needs::default();
+    };
+    let helpers = |names: &[&str]| -> Vec<Id> {
+        names
+            .iter()
+            .filter_map(|name| imports.find(name))
+            .map(Ident::to_id)
+            .collect()
+    };
+    let hooks = helpers(HOOKS);
+    let form_hooks = helpers(FORM_HOOKS);
+
+    let mut needs = Needs::default();
+    for stmt in &body.stmts {
+        match top_level_call(stmt) {
+            Some((callee, args)) if hooks.contains(&callee) => {
+                needs.lifecycle = true;
+                needs.form_associated |= form_hooks.contains(&callee);
+                // nested calls are reported below
+                args.visit_with(&mut Misplaced(&hooks));
+            }
+            _ => stmt.visit_with(&mut Misplaced(&hooks)),
+        }
+    }
+
+    let mut effects = References {
+        ids: helpers(EFFECTS),
+        found: false,
+    };
+    body.visit_with(&mut effects);
+    needs.lifecycle |= effects.found;
+    needs
+}
+
+/// `hook(...);` or `const value = hook(...);`
+fn top_level_call(stmt: &Stmt) -> Option<(Id, &[ExprOrSpread])> {
+    let expr = match stmt {
+        Stmt::Expr(ExprStmt { expr, .. }) => &**expr,
+        Stmt::Decl(Decl::Var(var)) => match var.decls.as_slice() {
+            [
+                VarDeclarator {
+                    init: Some(init), ..
+                },
+            ] => &**init,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        args,
+        ..
+    }) = expr
+    else {
+        return None;
+    };
+    let Expr::Ident(callee) = &**callee else {
+        return None;
+    };
+    Some((callee.to_id(), args))
+}
+
+/// Reports the calls of `hooks` it visits.
+struct Misplaced<'a>(&'a [Id]);
+
+impl Visit for Misplaced<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee
+            && let Expr::Ident(callee) = &**callee
+            && self.0.contains(&callee.to_id())
+        {
+            emit_error(
+                call.span,
+                &format!(
+                    "`{}` must be called at the top level of the component",
+                    callee.sym
+                ),
+            );
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// Finds references to `ids`.
+struct References {
+    ids: Vec<Id>,
+    found: bool,
+}
+
+impl Visit for References {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.found |= self.ids.contains(&ident.to_id());
+    }
+}
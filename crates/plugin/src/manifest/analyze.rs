@@ -0,0 +1,187 @@
+//! Native analysis of source files, running the transform outside a WASM host.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::errors::{HANDLER, Handler};
+use swc_core::common::sync::Lrc;
+use swc_core::common::{FileName, GLOBALS, Mark, SourceMap};
+use swc_core::ecma::ast::{EsVersion, Program};
+use swc_core::ecma::parser::{EsSyntax, Syntax, TsSyntax, parse_file_as_module};
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::visit::visit_mut_pass;
+
+use super::{JavaScriptModule, Manifest};
+use crate::{Config, FluxelTransform};
+
+/// Extensions of the modules [`generate`] analyzes.
+const EXTENSIONS: &[&str] = &["tsx", "ts", "jsx", "js", "mts", "mjs"];
+
+/// Why a manifest could not be generated.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the source tree failed.
+    Io { path: PathBuf, error: io::Error },
+    /// A module does not parse or compile; `message` holds the rendered
+    /// diagnostics.
+    Diagnostics { path: String, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Error::Diagnostics { path, message } => write!(f, "{path}:\n{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Describes the elements defined by the module `source`, `None` when it
+/// defines none. `path` names the module in the manifest and in diagnostics.
+pub fn analyze(
+    path: &str,
+    source: String,
+    config: &Config,
+) -> Result<Option<JavaScriptModule>, Error> {
+    let output = Output::default();
+    let cm: Lrc<SourceMap> = Default::default();
+    let handler = Handler::with_emitter_writer(Box::new(output.clone()), Some(cm.clone()));
+
+    let module = GLOBALS.set(&Default::default(), || {
+        HANDLER.set(&handler, || {
+            let fm = cm.new_source_file(FileName::Real(path.into()).into(), source);
+            let comments = SingleThreadedComments::default();
+            let mut recovered = vec![];
+            let module = match parse_file_as_module(
+                &fm,
+                syntax(path),
+                EsVersion::latest(),
+                Some(&comments),
+                &mut recovered,
+            ) {
+                Ok(module) => module,
+                Err(error) => {
+                    error.into_diagnostic(&handler).emit();
+                    return None;
+                }
+            };
+            for error in recovered {
+                error.into_diagnostic(&handler).emit();
+            }
+
+            let unresolved_mark = Mark::new();
+            let mut transform = FluxelTransform::new(config.clone(), unresolved_mark, &comments)
+                .with_filename(Some(path.to_string()));
+            Program::Module(module).apply((
+                resolver(unresolved_mark, Mark::new(), true),
+                visit_mut_pass(&mut transform),
+            ));
+            transform.take_manifest(path)
+        })
+    });
+
+    if handler.has_errors() {
+        return Err(Error::Diagnostics {
+            path: path.to_string(),
+            message: output.into_string(),
+        });
+    }
+    Ok(module)
+}
+
+/// Describes the elements defined by the modules under `root`, skipping
+/// `node_modules`, hidden directories and declaration files. Module paths
+/// are relative to `root`.
+pub fn generate(root: &Path, config: &Config) -> Result<Manifest, Error> {
+    let mut files = vec![];
+    collect(root, &mut files)?;
+    files.sort();
+
+    let mut modules = vec![];
+    for file in files {
+        let source = std::fs::read_to_string(&file).map_err(|error| Error::Io {
+            path: file.clone(),
+            error,
+        })?;
+        let path = file
+            .strip_prefix(root)
+            .unwrap_or(&file)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        modules.extend(analyze(&path, source, config)?);
+    }
+    Ok(Manifest::new(modules))
+}
+
+/// Adds the modules under `dir` to `files`.
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let io_error = |error| Error::Io {
+        path: dir.to_path_buf(),
+        error,
+    };
+    for entry in std::fs::read_dir(dir).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+        if entry.file_type().map_err(io_error)?.is_dir() {
+            collect(&path, files)?;
+        } else if is_module(&name) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Source module, as opposed to a declaration file such as `index.d.ts`.
+fn is_module(name: &str) -> bool {
+    let Some((stem, extension)) = name.rsplit_once('.') else {
+        return false;
+    };
+    EXTENSIONS.contains(&extension) && !stem.ends_with(".d")
+}
+
+/// Syntax of the module `path`, by its extension.
+fn syntax(path: &str) -> Syntax {
+    let extension = path.rsplit_once('.').map_or("", |(_, extension)| extension);
+    match extension {
+        "ts" | "mts" | "tsx" => Syntax::Typescript(TsSyntax {
+            tsx: extension == "tsx",
+            ..Default::default()
+        }),
+        _ => Syntax::Es(EsSyntax {
+            jsx: true,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Diagnostics rendered by the handler.
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Output {
+    fn into_string(self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
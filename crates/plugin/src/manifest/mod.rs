@@ -0,0 +1,262 @@
+//! [Custom Elements Manifest] of the lifted components, the schema IDEs,
+//! Storybook and documentation sites read custom elements from.
+//!
+//! [`FluxelTransform::take_manifest`](crate::FluxelTransform::take_manifest)
+//! describes the elements of the modules it transformed. With the `manifest`
+//! feature, [`generate`] analyzes a whole source tree without a WASM host,
+//! and the `fluxel-manifest` command writes the result:
+//!
+//! ```sh
+//! cargo run --features manifest --bin fluxel-manifest -- src --out custom-elements.json
+//! ```
+//!
+//! Descriptions come from the JSDoc of the components, of the members of
+//! their props type and of the events passed to `defineEvents`.
+//!
+//! [Custom Elements Manifest]: https://github.com/webcomponents/custom-elements-manifest
+
+use serde::Serialize;
+use swc_core::common::comments::Comments;
+
+use crate::component::{Component, Export as ComponentExport};
+use crate::jsdoc;
+
+#[cfg(feature = "manifest")]
+mod analyze;
+
+#[cfg(feature = "manifest")]
+pub use analyze::{Error, analyze, generate};
+
+/// Version of the schema the manifest follows.
+pub const SCHEMA_VERSION: &str = "2.1.0";
+
+/// Contents of `custom-elements.json`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub schema_version: String,
+    pub modules: Vec<JavaScriptModule>,
+}
+
+impl Manifest {
+    pub fn new(modules: Vec<JavaScriptModule>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            modules,
+        }
+    }
+}
+
+/// A module defining custom elements.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename = "javascript-module")]
+pub struct JavaScriptModule {
+    /// Path of the module, relative to the package root.
+    pub path: String,
+    pub declarations: Vec<CustomElementDeclaration>,
+    pub exports: Vec<Export>,
+}
+
+/// The element class of a component.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename = "class", rename_all = "camelCase")]
+pub struct CustomElementDeclaration {
+    /// Name of the generated class, e.g. `CounterElement`.
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub superclass: Reference,
+    pub custom_element: bool,
+    pub tag_name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Attribute>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<ClassField>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Event>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub slots: Vec<Slot>,
+}
+
+/// An observed attribute, backed by a prop.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attribute {
+    pub name: String,
+    pub field_name: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The accessor of a prop.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename = "field")]
+pub struct ClassField {
+    pub name: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Attribute setting the field.
+    pub attribute: String,
+    /// Whether the field is written back to its attribute.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reflects: bool,
+}
+
+/// A custom event dispatched by the element.
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub name: String,
+    /// Type of the event object, e.g. `CustomEvent<number>`.
+    #[serde(rename = "type")]
+    pub ty: Type,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A slot of the shadow root, `""` naming the default slot.
+#[derive(Debug, Clone, Serialize)]
+pub struct Slot {
+    pub name: String,
+}
+
+/// A TypeScript type, as written.
+#[derive(Debug, Clone, Serialize)]
+pub struct Type {
+    pub text: String,
+}
+
+/// What a module exports.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum Export {
+    /// A JavaScript export of the element class.
+    #[serde(rename = "js")]
+    Js {
+        name: String,
+        declaration: Reference,
+    },
+    /// The registration of the class under a tag name.
+    #[serde(rename = "custom-element-definition")]
+    CustomElementDefinition {
+        name: String,
+        declaration: Reference,
+    },
+}
+
+/// Reference to a declaration, in `module` or in `package`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Reference {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+/// Describes the elements of `components` as the module `path`.
+pub(crate) fn module(
+    components: &[Component],
+    comments: &dyn Comments,
+    path: &str,
+) -> JavaScriptModule {
+    let mut declarations = vec![];
+    let mut exports = vec![];
+    for component in components {
+        declarations.push(declaration(component, comments));
+
+        let class = || Reference {
+            name: component.class_ident.sym.to_string(),
+            package: None,
+            module: Some(path.to_string()),
+        };
+        let name = match component.export {
+            ComponentExport::Default => Some("default".to_string()),
+            ComponentExport::Named => Some(component.fn_ident.sym.to_string()),
+            ComponentExport::None => None,
+        };
+        if let Some(name) = name {
+            exports.push(Export::Js {
+                name,
+                declaration: class(),
+            });
+        }
+        exports.push(Export::CustomElementDefinition {
+            name: component.tag_name.clone(),
+            declaration: class(),
+        });
+    }
+
+    JavaScriptModule {
+        path: path.to_string(),
+        declarations,
+        exports,
+    }
+}
+
+fn declaration(component: &Component, comments: &dyn Comments) -> CustomElementDeclaration {
+    let ty = |text: &Option<String>| text.clone().map(|text| Type { text });
+
+    CustomElementDeclaration {
+        name: component.class_ident.sym.to_string(),
+        description: jsdoc::description(comments, component.doc),
+        superclass: Reference {
+            name: "HTMLElement".to_string(),
+            package: Some("global:".to_string()),
+            module: None,
+        },
+        custom_element: true,
+        tag_name: component.tag_name.clone(),
+        attributes: component
+            .props
+            .iter()
+            .map(|prop| Attribute {
+                name: prop.attr.clone(),
+                field_name: prop.name.to_string(),
+                ty: ty(&prop.ty),
+                default: prop.default.clone(),
+                description: prop.description.clone(),
+            })
+            .collect(),
+        members: component
+            .props
+            .iter()
+            .map(|prop| ClassField {
+                name: prop.name.to_string(),
+                ty: ty(&prop.ty),
+                default: prop.default.clone(),
+                description: prop.description.clone(),
+                attribute: prop.attr.clone(),
+                reflects: prop.reflect,
+            })
+            .collect(),
+        events: component
+            .events
+            .iter()
+            .map(|event| Event {
+                name: event.name.to_string(),
+                ty: Type {
+                    text: match &event.detail {
+                        Some(detail) => format!("CustomEvent<{detail}>"),
+                        None => "CustomEvent".to_string(),
+                    },
+                },
+                description: event.description.clone(),
+            })
+            .collect(),
+        slots: component
+            .slots
+            .iter()
+            .map(|name| Slot {
+                name: name.to_string(),
+            })
+            .collect(),
+    }
+}
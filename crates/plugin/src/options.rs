@@ -0,0 +1,244 @@
+//! Per-component options.
+//!
+//! A module configures the elements it defines through a static `config`
+//! export, which is removed from the output unless the module reads it. Any
+//! other `config`, i.e. not an object literal of the options listed below,
+//! belongs to the module and is left alone:
+//!
+//! ```ts
+//! export const config = { shadow: "closed", delegatesFocus: true };
+//! ```
+//!
+//! JSDoc tags on a component take precedence over the module's `config`:
+//!
+//! ```ts
+//! /** @shadow false */
+//! export default function Banner() {}
+//! ```
+//!
+//! | Option           | Values                                                        |
+//! | ---------------- | ------------------------------------------------------------- |
+//! | `shadow`         | `"open"`, `"closed"`, or `false` to render into the light DOM |
+//! | `delegatesFocus` | boolean                                                       |
+//! | `slotAssignment` | `"named"` or `"manual"`                                       |
+//! | `formAssociated` | boolean, see [`crate::lifecycle`]                             |
+
+use swc_core::common::comments::Comments;
+use swc_core::common::{BytePos, Span};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::config::{Config, ShadowMode};
+use crate::jsdoc;
+use crate::utils::emit_error;
+
+/// Name of the exported options object.
+const CONFIG_EXPORT: &str = "config";
+
+/// Names of the options.
+const KEYS: &[&str] = &[
+    "shadow",
+    "delegatesFocus",
+    "slotAssignment",
+    "formAssociated",
+];
+
+/// Options of the elements of a module or of a single component.
+#[derive(Debug, Clone)]
+pub(crate) struct Options {
+    /// Mode of the shadow root, `None` for light DOM.
+    shadow: Option<ShadowMode>,
+    delegates_focus: bool,
+    slot_assignment: Option<SlotAssignment>,
+    pub form_associated: bool,
+}
+
+/// `init` argument of `attachShadow`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ShadowRoot {
+    pub mode: ShadowMode,
+    pub delegates_focus: bool,
+    pub slot_assignment: Option<SlotAssignment>,
+}
+
+/// `slotAssignment` of a shadow root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SlotAssignment {
+    Named,
+    Manual,
+}
+
+impl SlotAssignment {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            SlotAssignment::Named => "named",
+            SlotAssignment::Manual => "manual",
+        }
+    }
+}
+
+/// Static value of an option.
+enum Value {
+    Str(String),
+    Bool(bool),
+}
+
+impl Options {
+    /// Options of the module `m`, taking its `config` export out once read,
+    /// unless the module reads it too.
+    pub fn from_module(m: &mut Module, config: &Config) -> Self {
+        let mut options = Self {
+            shadow: Some(config.shadow_mode),
+            delegates_focus: false,
+            slot_assignment: None,
+            form_associated: false,
+        };
+
+        let Some(idx) = m.body.iter().position(is_config_export) else {
+            return options;
+        };
+        let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Var(var),
+            ..
+        })) = &m.body[idx]
+        else {
+            unreachable!("checked by is_config_export()");
+        };
+        // e.g. `export const config = { apiUrl: "/api" };` of the module itself
+        let Some(entries) = static_options(var) else {
+            return options;
+        };
+        for (key, value, span) in entries {
+            options.set(key, value, span);
+        }
+        if !is_referenced(m, idx, &var.decls[0].name) {
+            m.body.remove(idx);
+        }
+        options
+    }
+
+    /// These options overridden by the JSDoc tags of the component declared
+    /// at `doc`; invalid tags are reported at `span`.
+    pub fn with_doc(&self, comments: &dyn Comments, doc: BytePos, span: Span) -> Self {
+        let mut options = self.clone();
+        for &key in KEYS {
+            // a bare `@delegatesFocus` turns the option on
+            let value = match jsdoc::tag(comments, doc, key).as_deref() {
+                None => continue,
+                Some("" | "true") => Value::Bool(true),
+                Some("false") => Value::Bool(false),
+                Some(value) => Value::Str(value.to_string()),
+            };
+            options.set(key, value, span);
+        }
+        options
+    }
+
+    /// The shadow root to attach, `None` rendering into the light DOM.
+    pub fn shadow_root(&self) -> Option<ShadowRoot> {
+        Some(ShadowRoot {
+            mode: self.shadow?,
+            delegates_focus: self.delegates_focus,
+            slot_assignment: self.slot_assignment,
+        })
+    }
+
+    fn set(&mut self, key: &str, value: Value, span: Span) {
+        match (key, value) {
+            ("shadow", Value::Str(mode)) if mode == "open" => self.shadow = Some(ShadowMode::Open),
+            ("shadow", Value::Str(mode)) if mode == "closed" => {
+                self.shadow = Some(ShadowMode::Closed)
+            }
+            ("shadow", Value::Bool(false)) => self.shadow = None,
+            ("shadow", _) => emit_error(span, "`shadow` must be \"open\", \"closed\" or false"),
+            ("delegatesFocus", Value::Bool(on)) => self.delegates_focus = on,
+            ("slotAssignment", Value::Str(kind)) if kind == "named" => {
+                self.slot_assignment = Some(SlotAssignment::Named)
+            }
+            ("slotAssignment", Value::Str(kind)) if kind == "manual" => {
+                self.slot_assignment = Some(SlotAssignment::Manual)
+            }
+            ("slotAssignment", _) => {
+                emit_error(span, "`slotAssignment` must be \"named\" or \"manual\"")
+            }
+            ("formAssociated", Value::Bool(on)) => self.form_associated = on,
+            ("delegatesFocus" | "formAssociated", _) => {
+                emit_error(span, &format!("`{key}` must be a boolean"))
+            }
+            _ => emit_error(span, &format!("unknown component option `{key}`")),
+        }
+    }
+}
+
+/// `export const config = ...;`
+fn is_config_export(item: &ModuleItem) -> bool {
+    let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        decl: Decl::Var(var),
+        ..
+    })) = item
+    else {
+        return false;
+    };
+    matches!(var.decls.as_slice(), [VarDeclarator {
+        name: Pat::Ident(name),
+        ..
+    }] if name.id.sym == CONFIG_EXPORT)
+}
+
+/// The `key: value` pairs of `export const config = { ... }` when it is a
+/// static object of known options, `None` for any other `config`.
+fn static_options(var: &VarDecl) -> Option<Vec<(&str, Value, Span)>> {
+    let Some(Expr::Object(object)) = var.decls[0].init.as_deref() else {
+        return None;
+    };
+    object
+        .props
+        .iter()
+        .map(|prop| {
+            let PropOrSpread::Prop(prop) = prop else {
+                return None;
+            };
+            let Prop::KeyValue(KeyValueProp { key, value }) = &**prop else {
+                return None;
+            };
+            let (key, span) = match key {
+                PropName::Ident(key) => (key.sym.as_str(), key.span),
+                PropName::Str(key) => (&*key.value, key.span),
+                _ => return None,
+            };
+            let value = match &**value {
+                Expr::Lit(Lit::Str(value)) => Value::Str(value.value.to_string()),
+                Expr::Lit(Lit::Bool(value)) => Value::Bool(value.value),
+                _ => return None,
+            };
+            KEYS.contains(&key).then_some((key, value, span))
+        })
+        .collect()
+}
+
+/// Whether an item of `m` other than the `config` export at `idx` reads the
+/// binding `name`, e.g. `export { config as defaults }`.
+fn is_referenced(m: &Module, idx: usize, name: &Pat) -> bool {
+    struct References {
+        id: Id,
+        found: bool,
+    }
+
+    impl Visit for References {
+        fn visit_ident(&mut self, ident: &Ident) {
+            self.found |= ident.to_id() == self.id;
+        }
+    }
+
+    let Pat::Ident(name) = name else {
+        unreachable!("checked by is_config_export()");
+    };
+    let mut references = References {
+        id: name.to_id(),
+        found: false,
+    };
+    for (_, item) in m.body.iter().enumerate().filter(|(i, _)| *i != idx) {
+        item.visit_with(&mut references);
+    }
+    references.found
+}
@@ -0,0 +1,251 @@
+//! Props of a component and their signal-backed rewrite.
+//!
+//! Every prop of a lifted component is stored in a signal owned by the
+//! element, so the component receives `{ initial: Signal }` instead of plain
+//! values. Reads of the destructured bindings are rewritten accordingly:
+//!
+//! ```jsx
+//! function Counter({ initial = 0 }) { return <p>{initial}</p>; }
+//! // becomes
+//! function Counter(__props) { return <p>{__props.initial.value ?? 0}</p>; }
+//! ```
+
+use std::collections::HashMap;
+
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::atoms::Atom;
+use swc_core::ecma::codegen::to_code;
+use swc_core::ecma::utils::private_ident;
+use swc_core::ecma::visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+use swc_core::common::comments::Comments;
+
+use crate::types::{PropKind, PropType, TypeDecls};
+use crate::utils::{kebab_case, member};
+
+/// A single prop of a component.
+pub(crate) struct ComponentProp {
+    /// Property name, e.g. `initialValue`.
+    pub name: Atom,
+    /// Observed attribute name, e.g. `initial-value`.
+    pub attr: String,
+    /// Conversion applied to the attribute value.
+    pub kind: PropKind,
+    /// Whether changes of the prop are written back to the attribute.
+    pub reflect: bool,
+    /// TypeScript type the prop is annotated with.
+    pub ty: Option<String>,
+    /// Default value of the destructured binding, as written.
+    pub default: Option<String>,
+    /// JSDoc of the prop in the props type.
+    pub description: Option<String>,
+}
+
+impl ComponentProp {
+    fn new(name: Atom, types: &HashMap<Atom, PropType>, default: Option<&Expr>) -> Self {
+        let attr = kebab_case(&name);
+        let PropType {
+            kind,
+            reflect,
+            ty,
+            description,
+        } = types.get(&name).cloned().unwrap_or_default();
+        Self {
+            name,
+            attr,
+            kind,
+            reflect,
+            ty,
+            default: default.map(to_code),
+            description,
+        }
+    }
+}
+
+/// Collects the props of `function` from its first parameter and rewrites the
+/// function to take the signal store instead.
+///
+/// Returns the parameter the store is bound to, if the component takes props.
+pub(crate) fn lift_props(
+    function: &mut Function,
+    types: &TypeDecls,
+    comments: &dyn Comments,
+) -> (Vec<ComponentProp>, Option<Ident>) {
+    let Some(param) = function.params.first_mut() else {
+        return (vec![], None);
+    };
+
+    let types = match &param.pat {
+        Pat::Object(ObjectPat { type_ann, .. }) | Pat::Ident(BindingIdent { type_ann, .. }) => {
+            type_ann
+                .as_ref()
+                .map(|ann| types.prop_types(&ann.type_ann, comments))
+                .unwrap_or_default()
+        }
+        _ => HashMap::new(),
+    };
+
+    let (props, reads) = match &param.pat {
+        // function Counter({ initial = 0, label: text }) {}
+        Pat::Object(pat) => destructured_props(pat, &types),
+        // function Counter(props) { props.initial }
+        Pat::Ident(ident) => {
+            let mut collector = MemberProps {
+                obj: ident.to_id(),
+                names: vec![],
+            };
+            function.body.visit_with(&mut collector);
+            let props = collector
+                .names
+                .into_iter()
+                .map(|name| ComponentProp::new(name, &types, None))
+                .collect();
+            (props, HashMap::new())
+        }
+        _ => return (vec![], None),
+    };
+
+    let store = match &param.pat {
+        Pat::Ident(ident) => ident.id.clone(),
+        _ => private_ident!("__props"),
+    };
+    param.pat = store.clone().into();
+
+    function.body.visit_mut_with(&mut PropReads {
+        store: store.clone(),
+        reads,
+        names: props.iter().map(|p| p.name.clone()).collect(),
+    });
+
+    (props, Some(store))
+}
+
+/// Local binding of a destructured prop and its default value.
+type Read = (Atom, Option<Box<Expr>>);
+
+fn destructured_props(
+    pat: &ObjectPat,
+    types: &HashMap<Atom, PropType>,
+) -> (Vec<ComponentProp>, HashMap<Id, Read>) {
+    let mut props = vec![];
+    let mut reads = HashMap::new();
+
+    for prop in &pat.props {
+        let (name, local, default) = match prop {
+            // { initial = 0 }
+            ObjectPatProp::Assign(assign) => (
+                assign.key.sym.clone(),
+                assign.key.to_id(),
+                assign.value.clone(),
+            ),
+            // { label: text } / { label: text = "" }
+            ObjectPatProp::KeyValue(kv) => {
+                let name = match &kv.key {
+                    PropName::Ident(name) => name.sym.clone(),
+                    PropName::Str(name) => name.value.clone(),
+                    _ => continue,
+                };
+                match &*kv.value {
+                    Pat::Ident(local) => (name, local.to_id(), None),
+                    Pat::Assign(AssignPat { left, right, .. }) => match &**left {
+                        Pat::Ident(local) => (name, local.to_id(), Some(right.clone())),
+                        _ => continue,
+                    },
+                    _ => continue,
+                }
+            }
+            ObjectPatProp::Rest(_) => continue,
+        };
+
+        props.push(ComponentProp::new(name.clone(), types, default.as_deref()));
+        reads.insert(local, (name, default));
+    }
+
+    (props, reads)
+}
+
+/// Collects `props.<name>` accesses on the props parameter.
+struct MemberProps {
+    obj: Id,
+    names: Vec<Atom>,
+}
+
+impl Visit for MemberProps {
+    fn visit_member_expr(&mut self, e: &MemberExpr) {
+        e.visit_children_with(self);
+
+        if let (Expr::Ident(obj), MemberProp::Ident(prop)) = (&*e.obj, &e.prop)
+            && obj.to_id() == self.obj
+            && !self.names.contains(&prop.sym)
+        {
+            self.names.push(prop.sym.clone());
+        }
+    }
+}
+
+/// Rewrites prop reads into signal reads on the store.
+struct PropReads {
+    store: Ident,
+    /// Destructured bindings.
+    reads: HashMap<Id, Read>,
+    /// Prop names, for `props.<name>` accesses.
+    names: Vec<Atom>,
+}
+
+impl PropReads {
+    /// `__props.<name>.value ?? <default>`
+    fn read(&self, name: &str, default: Option<Box<Expr>>) -> Expr {
+        let value = member(member(self.store.clone(), name), "value");
+        match default {
+            Some(default) => Expr::Paren(ParenExpr {
+                span: DUMMY_SP,
+                expr: Box::new(Expr::Bin(BinExpr {
+                    span: DUMMY_SP,
+                    op: op!("??"),
+                    left: Box::new(value),
+                    right: default,
+                })),
+            }),
+            None => value,
+        }
+    }
+}
+
+impl VisitMut for PropReads {
+    fn visit_mut_expr(&mut self, e: &mut Expr) {
+        match e {
+            Expr::Ident(ident) => {
+                if let Some((name, default)) = self.reads.get(&ident.to_id()) {
+                    *e = self.read(name, default.clone());
+                }
+            }
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) if obj
+                .as_ident()
+                .is_some_and(|i| i.to_id() == self.store.to_id())
+                && self.names.contains(&prop.sym) =>
+            {
+                *e = self.read(&prop.sym, None);
+            }
+            _ => e.visit_mut_children_with(self),
+        }
+    }
+
+    fn visit_mut_prop(&mut self, p: &mut Prop) {
+        // { initial } -> { initial: __props.initial.value }
+        if let Prop::Shorthand(ident) = p
+            && let Some((name, default)) = self.reads.get(&ident.to_id())
+        {
+            *p = Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(ident.clone().into()),
+                value: Box::new(self.read(name, default.clone())),
+            });
+            return;
+        }
+        p.visit_mut_children_with(self);
+    }
+}
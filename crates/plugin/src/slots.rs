@@ -0,0 +1,255 @@
+//! Content projection through `<slot>` elements.
+//!
+//! The children of a custom element stay in its light DOM, so the component
+//! never receives them. References to them become slots instead:
+//!
+//! ```jsx
+//! function Card({ children, slots }) {
+//!   return <article>{slots.header}<Slot name="footer">Fallback</Slot>{children}</article>;
+//! }
+//! // becomes
+//! function Card() {
+//!   return <article><slot name="header" /><slot name="footer">Fallback</slot><slot /></article>;
+//! }
+//! ```
+//!
+//! `props.children` and `props.slots.header` are handled the same way, and
+//! `<Slot>` is the component of the runtime module. Slots can only be
+//! rendered, as JSX children or returned, possibly through `cond ? a : b` or
+//! `cond && a`; other reads, e.g. `!!children`, are reported.
+
+use swc_core::common::{DUMMY_SP, Spanned};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::atoms::Atom;
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::imports::RuntimeImports;
+use crate::utils::emit_error;
+
+/// Rewrites the slots of `function` and drops `children` and `slots` from its
+/// props. Returns the names of the slots it renders, `""` for the default
+/// slot.
+pub(crate) fn lower_slots(function: &mut Function, imports: &RuntimeImports) -> Vec<Atom> {
+    let mut slots = Slots {
+        props: None,
+        children: None,
+        slots: None,
+        slot: imports.find("Slot").map(Ident::to_id),
+        names: vec![],
+    };
+
+    match function.params.first_mut().map(|param| &mut param.pat) {
+        // function Card(props) { props.children }
+        Some(Pat::Ident(props)) => slots.props = Some(props.to_id()),
+        // function Card({ children, slots: named }) {}
+        Some(Pat::Object(pat)) => pat.props.retain(|prop| {
+            let Some((name, local)) = binding(prop) else {
+                return true;
+            };
+            match &*name {
+                "children" => slots.children = Some(local),
+                "slots" => slots.slots = Some(local),
+                _ => return true,
+            }
+            false
+        }),
+        _ => {}
+    }
+
+    function.body.visit_mut_with(&mut slots);
+    slots.names
+}
+
+/// Name and local binding of a destructured prop.
+fn binding(prop: &ObjectPatProp) -> Option<(Atom, Id)> {
+    match prop {
+        ObjectPatProp::Assign(assign) => Some((assign.key.sym.clone(), assign.key.to_id())),
+        ObjectPatProp::KeyValue(kv) => {
+            let name = match &kv.key {
+                PropName::Ident(name) => name.sym.clone(),
+                PropName::Str(name) => name.value.clone(),
+                _ => return None,
+            };
+            match &*kv.value {
+                Pat::Ident(local) => Some((name, local.to_id())),
+                _ => None,
+            }
+        }
+        ObjectPatProp::Rest(_) => None,
+    }
+}
+
+struct Slots {
+    /// Props parameter, when not destructured.
+    props: Option<Id>,
+    /// Destructured `children`.
+    children: Option<Id>,
+    /// Destructured `slots`.
+    slots: Option<Id>,
+    /// `Slot` imported from the runtime.
+    slot: Option<Id>,
+    names: Vec<Atom>,
+}
+
+impl Slots {
+    /// The slot `expr` refers to: `None` for the default slot.
+    fn slot_of(&self, expr: &Expr) -> Option<Option<Atom>> {
+        let is = |expr: &Expr, id: &Option<Id>| match expr {
+            Expr::Ident(ident) => id.as_ref() == Some(&ident.to_id()),
+            _ => false,
+        };
+        // props.<name>
+        let props_member = |expr: &Expr, name: &str| {
+            matches!(expr, Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) if is(obj, &self.props) && prop.sym == name)
+        };
+
+        match expr {
+            // children, props.children
+            expr if is(expr, &self.children) || props_member(expr, "children") => Some(None),
+            // slots.header, props.slots.header, slots["header"]
+            Expr::Member(MemberExpr { obj, prop, .. })
+                if is(obj, &self.slots) || props_member(obj, "slots") =>
+            {
+                match prop {
+                    MemberProp::Ident(name) => Some(Some(name.sym.clone())),
+                    MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                        Expr::Lit(Lit::Str(name)) => Some(Some(name.value.clone())),
+                        _ => None,
+                    },
+                    MemberProp::PrivateName(_) => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn add(&mut self, name: Atom) {
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+    }
+
+    /// Rewrites the slots `expr` renders: the expression itself, the branches
+    /// of a condition and the right operand of `&&`, `||` and `??`.
+    fn render(&mut self, expr: &mut Expr) {
+        if let Some(slot) = self.take_slot(expr) {
+            *expr = Expr::JSXElement(Box::new(slot));
+            return;
+        }
+        match expr {
+            Expr::Paren(paren) => self.render(&mut paren.expr),
+            Expr::Cond(cond) => {
+                cond.test.visit_mut_with(self);
+                self.render(&mut cond.cons);
+                self.render(&mut cond.alt);
+            }
+            Expr::Bin(bin) if matches!(bin.op, op!("&&") | op!("||") | op!("??")) => {
+                bin.left.visit_mut_with(self);
+                self.render(&mut bin.right);
+            }
+            expr => expr.visit_mut_with(self),
+        }
+    }
+
+    fn take_slot(&mut self, expr: &Expr) -> Option<JSXElement> {
+        let name = self.slot_of(expr)?;
+        self.add(name.clone().unwrap_or_default());
+        let attrs = name
+            .map(|name| {
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    span: DUMMY_SP,
+                    name: JSXAttrName::Ident("name".into()),
+                    value: Some(JSXAttrValue::Lit(Lit::Str(name.into()))),
+                })
+            })
+            .into_iter()
+            .collect();
+        Some(JSXElement {
+            span: DUMMY_SP,
+            opening: JSXOpeningElement {
+                name: slot_name(),
+                span: DUMMY_SP,
+                attrs,
+                self_closing: true,
+                type_args: None,
+            },
+            children: vec![],
+            closing: None,
+        })
+    }
+}
+
+impl VisitMut for Slots {
+    fn visit_mut_jsx_element_child(&mut self, child: &mut JSXElementChild) {
+        // {children} -> <slot />
+        if let JSXElementChild::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        }) = child
+        {
+            match self.take_slot(expr) {
+                Some(slot) => *child = JSXElementChild::JSXElement(Box::new(slot)),
+                None => self.render(expr),
+            }
+            return;
+        }
+        child.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_return_stmt(&mut self, stmt: &mut ReturnStmt) {
+        if let Some(arg) = &mut stmt.arg {
+            self.render(arg);
+        }
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        arrow.params.visit_mut_with(self);
+        match &mut *arrow.body {
+            BlockStmtOrExpr::Expr(body) => self.render(body),
+            body => body.visit_mut_with(self),
+        }
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        // the children stay in the light DOM, the component never sees them
+        if self.slot_of(expr).is_some() {
+            emit_error(
+                expr.span(),
+                "slotted children can only be rendered, as JSX children or returned",
+            );
+            return;
+        }
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_jsx_element(&mut self, el: &mut JSXElement) {
+        // <Slot name="footer">...</Slot> -> <slot name="footer">...</slot>
+        if let JSXElementName::Ident(name) = &el.opening.name
+            && self.slot.as_ref() == Some(&name.to_id())
+        {
+            // the name of a dynamic slot is only known at runtime
+            let name = el.opening.attrs.iter().find_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(name),
+                    value: Some(JSXAttrValue::Lit(Lit::Str(value))),
+                    ..
+                }) if name.sym == "name" => Some(value.value.clone()),
+                _ => None,
+            });
+            self.add(name.unwrap_or_default());
+            el.opening.name = slot_name();
+            if let Some(closing) = &mut el.closing {
+                closing.name = slot_name();
+            }
+        }
+        el.visit_mut_children_with(self);
+    }
+}
+
+fn slot_name() -> JSXElementName {
+    JSXElementName::Ident("slot".into())
+}
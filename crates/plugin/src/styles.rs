@@ -0,0 +1,262 @@
+//! Component styles.
+//!
+//! The styles of a module are the default imports of `.css` files loaded as
+//! text through a `?inline` or `?raw` query, and the module-level `css`
+//! tagged templates:
+//!
+//! ```ts
+//! import base from "./base.css?inline";
+//! const styles = css`p { color: tomato; }`;
+//! ```
+//!
+//! Each one is parsed once into a Constructable StyleSheet, adopted by the
+//! shadow root of every element the module defines:
+//!
+//! ```js
+//! const _sheet = new CSSStyleSheet();
+//! _sheet.replaceSync(styles);
+//! // constructor
+//! _root.adoptedStyleSheets = [_sheet1, _sheet];
+//! ```
+//!
+//! With [`Config::style_tags`](crate::Config::style_tags) the text is put
+//! into `<style>` elements instead.
+//!
+//! Other `.css` imports are left to the bundler: `.module.css` ones are class
+//! maps, and plain ones only add the stylesheet to the document.
+//!
+//! Templates without interpolations are checked at build time, see
+//! [`crate::css`], and minified outside of [`Config::dev`](crate::Config::dev)
+//! builds.
+
+use swc_core::common::{BytePos, DUMMY_SP, Span, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::private_ident;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::config::Config;
+use crate::css;
+use crate::imports::RuntimeImports;
+use crate::utils::{call, const_decl, emit_error, emit_warning, ident, member};
+
+/// Binding holding the text of a style, and the item declaring it.
+struct Source {
+    item: usize,
+    text: Ident,
+    /// Span of the `css` template initializing `text`.
+    template: Option<Span>,
+}
+
+/// Collects the styles of `m`. Returns the bindings the element constructor
+/// applies, in source order: hoisted `CSSStyleSheet`s, or the style texts
+/// themselves with `style_tags`.
+pub(crate) fn hoist_styles(
+    m: &mut Module,
+    imports: &RuntimeImports,
+    config: &Config,
+    unresolved: SyntaxContext,
+) -> Vec<Ident> {
+    let css = imports.find("css").map(Ident::to_id);
+    let sources = sources(m, css.as_ref());
+    report_nested_templates(m, css.as_ref(), &sources);
+    for source in &sources {
+        if let Some(tpl) = template_mut(m, source) {
+            check_template(tpl, !config.dev);
+        }
+    }
+
+    if config.style_tags {
+        return sources.into_iter().map(|source| source.text).collect();
+    }
+
+    // const _sheet = new CSSStyleSheet();
+    // _sheet.replaceSync(styles);
+    let mut sheets = Vec::with_capacity(sources.len());
+    for source in sources.iter().rev() {
+        let sheet = private_ident!("_sheet");
+        let create = NewExpr {
+            span: DUMMY_SP,
+            callee: Box::new(ident(unresolved, "CSSStyleSheet").into()),
+            args: Some(vec![]),
+            ..Default::default()
+        };
+        let replace = call(
+            member(sheet.clone(), "replaceSync"),
+            vec![source.text.clone().into()],
+        );
+        m.body.splice(
+            source.item + 1..source.item + 1,
+            [
+                ModuleItem::Stmt(const_decl(sheet.clone(), create.into())),
+                ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                    span: DUMMY_SP,
+                    expr: Box::new(replace),
+                })),
+            ],
+        );
+        sheets.push(sheet);
+    }
+    sheets.reverse();
+    sheets
+}
+
+fn sources(m: &Module, css: Option<&Id>) -> Vec<Source> {
+    let mut sources = vec![];
+
+    for (item, module_item) in m.body.iter().enumerate() {
+        match module_item {
+            // import styles from "./counter.css?inline";
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                if !import.type_only && is_css_file(&import.src.value) =>
+            {
+                let src = &import.src.value;
+                sources.extend(import.specifiers.iter().filter_map(|specifier| {
+                    let ImportSpecifier::Default(default) = specifier else {
+                        return None;
+                    };
+                    if !is_css_text(src) {
+                        if !is_css_module(src) {
+                            let message = format!(
+                                "`{src}` is not imported as text; add `?inline` to adopt it into the shadow root"
+                            );
+                            emit_warning(default.local.span, &message);
+                        }
+                        return None;
+                    }
+                    Some(Source {
+                        item,
+                        text: default.local.clone(),
+                        template: None,
+                    })
+                }))
+            }
+            // [export] const styles = css`...`;
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var)))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var),
+                ..
+            })) if var.kind == VarDeclKind::Const => {
+                sources.extend(var.decls.iter().filter_map(|decl| {
+                    let Pat::Ident(name) = &decl.name else {
+                        return None;
+                    };
+                    let Some(Expr::TaggedTpl(tpl)) = decl.init.as_deref() else {
+                        return None;
+                    };
+                    is_css_tag(&tpl.tag, css?).then(|| Source {
+                        item,
+                        text: name.id.clone(),
+                        template: Some(tpl.span),
+                    })
+                }))
+            }
+            _ => {}
+        }
+    }
+
+    sources
+}
+
+/// The `css` template initializing `source`.
+fn template_mut<'a>(m: &'a mut Module, source: &Source) -> Option<&'a mut Tpl> {
+    let var = match &mut m.body[source.item] {
+        ModuleItem::Stmt(Stmt::Decl(Decl::Var(var)))
+        | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Var(var),
+            ..
+        })) => var,
+        _ => return None,
+    };
+    var.decls
+        .iter_mut()
+        .find_map(|decl| match (&decl.name, decl.init.as_deref_mut()) {
+            (Pat::Ident(name), Some(Expr::TaggedTpl(tagged))) if name.id == source.text => {
+                Some(&mut *tagged.tpl)
+            }
+            _ => None,
+        })
+}
+
+/// Reports the problems of a static template and minifies it in place.
+fn check_template(tpl: &mut Tpl, minify: bool) {
+    let [quasi] = tpl.quasis.as_mut_slice() else {
+        return; // interpolated values are only known at runtime
+    };
+    let span = |offset: usize| {
+        let lo = quasi.span.lo + BytePos(offset as u32);
+        Span::new(lo, lo)
+    };
+
+    match css::check(&quasi.raw, minify) {
+        Err(error) => emit_error(span(error.offset), &error.message),
+        Ok(checked) => {
+            for warning in checked.warnings {
+                emit_warning(span(warning.offset), &warning.message);
+            }
+            if let Some(minified) = checked.minified {
+                // keep the template literal intact; `css` reads the raw text
+                let raw = minified.replace('`', "\\`").replace("${", "$\\{");
+                quasi.cooked = Some(minified.into());
+                quasi.raw = raw.into();
+            }
+        }
+    }
+}
+
+/// `css` templates anywhere else are evaluated per render and never adopted.
+fn report_nested_templates(m: &Module, css: Option<&Id>, sources: &[Source]) {
+    struct Templates<'a> {
+        css: &'a Id,
+        spans: Vec<Span>,
+    }
+
+    impl Visit for Templates<'_> {
+        fn visit_tagged_tpl(&mut self, tpl: &TaggedTpl) {
+            if is_css_tag(&tpl.tag, self.css) {
+                self.spans.push(tpl.span);
+            }
+            tpl.visit_children_with(self);
+        }
+    }
+
+    let Some(css) = css else {
+        return;
+    };
+    let mut templates = Templates { css, spans: vec![] };
+    m.visit_with(&mut templates);
+    for span in templates.spans {
+        if !sources.iter().any(|source| source.template == Some(span)) {
+            emit_error(
+                span,
+                "`css` styles must initialize a module-level `const` to be adopted",
+            );
+        }
+    }
+}
+
+fn is_css_tag(tag: &Expr, css: &Id) -> bool {
+    matches!(tag, Expr::Ident(tag) if tag.to_id() == *css)
+}
+
+/// `./counter.css`, `./counter.css?inline`
+fn is_css_file(src: &str) -> bool {
+    let path = src.split(['?', '#']).next().unwrap_or(src);
+    path.ends_with(".css")
+}
+
+/// `./counter.module.css`, whose default export maps class names.
+fn is_css_module(src: &str) -> bool {
+    let path = src.split(['?', '#']).next().unwrap_or(src);
+    path.ends_with(".module.css")
+}
+
+/// `./counter.css?inline`, `./counter.css?raw`: imports of the text itself.
+fn is_css_text(src: &str) -> bool {
+    let query = src.split('#').next().unwrap_or(src).split_once('?');
+    let text = query.is_some_and(|(_, query)| {
+        query
+            .split('&')
+            .any(|param| param == "inline" || param == "raw")
+    });
+    text && !is_css_module(src)
+}
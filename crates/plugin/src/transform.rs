@@ -0,0 +1,296 @@
+//! Module-level pass that lifts the component functions of a module into
+//! Custom Element classes.
+
+use swc_core::common::comments::Comments;
+use swc_core::common::{DUMMY_SP, Mark, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::component::{Component, Export, validate_tag_name};
+use crate::config::Config;
+use crate::discover::find_components;
+use crate::element::define_element;
+use crate::emit::lower_emits;
+use crate::imports::RuntimeImports;
+use crate::jsx::{Elements, JsxLowering, Signals, collect_signals};
+use crate::lifecycle;
+use crate::manifest::{self, JavaScriptModule};
+use crate::options::Options;
+use crate::props::lift_props;
+use crate::slots::lower_slots;
+use crate::styles::hoist_styles;
+use crate::types::TypeDecls;
+use crate::utils::emit_error;
+
+/// Lifts the components of a module (see [`crate::discover`]) into
+/// `HTMLElement` subclasses and registers them with `customElements.define`.
+///
+/// Modules without components, as well as scripts, are left untouched.
+pub struct FluxelTransform<C: Comments> {
+    config: Config,
+    unresolved_ctxt: SyntaxContext,
+    comments: C,
+    filename: Option<String>,
+    /// Components lifted so far, for [`FluxelTransform::take_manifest`].
+    lifted: Vec<Component>,
+}
+
+impl<C: Comments> FluxelTransform<C> {
+    /// `unresolved_mark` is the mark the resolver applied to global references
+    /// such as `HTMLElement` and `customElements`; `comments` carries the JSDoc
+    /// annotations of the module.
+    pub fn new(config: Config, unresolved_mark: Mark, comments: C) -> Self {
+        Self {
+            config,
+            unresolved_ctxt: SyntaxContext::empty().apply_mark(unresolved_mark),
+            comments,
+            filename: None,
+            lifted: vec![],
+        }
+    }
+
+    /// Path of the module being transformed, which names anonymous default
+    /// exports: `date-picker.tsx` declares `DatePicker` / `fluxel-date-picker`.
+    pub fn with_filename(mut self, filename: Option<String>) -> Self {
+        self.filename = filename;
+        self
+    }
+
+    /// Describes the elements lifted so far as the module `path` of a
+    /// [`Manifest`](crate::manifest::Manifest), `None` when there are none.
+    pub fn take_manifest(&mut self, path: &str) -> Option<JavaScriptModule> {
+        if self.lifted.is_empty() {
+            return None;
+        }
+        let components = std::mem::take(&mut self.lifted);
+        Some(manifest::module(&components, &self.comments, path))
+    }
+}
+
+impl<C: Comments> VisitMut for FluxelTransform<C> {
+    fn visit_mut_module(&mut self, m: &mut Module) {
+        let mut imports = RuntimeImports::from_module(m, &self.config.jsx_import_source);
+
+        // 1. find the components and turn each into a plain function declaration
+        let components = find_components(
+            m,
+            &self.comments,
+            &imports,
+            self.filename.as_deref(),
+            &self.config.tag_prefix,
+        );
+        if components.is_empty() {
+            return; // nothing to transform
+        }
+
+        if !validate_tag_names(&components) {
+            return;
+        }
+
+        let options = Options::from_module(m, &self.config);
+        let types = TypeDecls::from_module(m);
+        let signals = collect_signals(m, &imports, self.unresolved_ctxt);
+        let styles = hoist_styles(m, &imports, &self.config, self.unresolved_ctxt);
+        let components: Vec<_> = components
+            .into_iter()
+            .filter_map(|mut component| {
+                let options =
+                    options.with_doc(&self.comments, component.doc, component.fn_ident.span);
+                component.shadow = options.shadow_root();
+                component.form_associated = options.form_associated;
+                component.styles = styles.clone();
+                self.check_light_dom(&component).then_some(component)
+            })
+            .collect();
+        // JSX using a component renders its element
+        let elements = Elements::new(m, &components);
+        for component in components {
+            self.lift(m, component, &types, &signals, &elements, &mut imports);
+        }
+        imports.inject(m);
+    }
+}
+
+impl<C: Comments> FluxelTransform<C> {
+    /// Reports the features a component rendering into the light DOM cannot
+    /// use; such components are left alone.
+    fn check_light_dom(&self, component: &Component) -> bool {
+        if component.shadow.is_some() {
+            return true;
+        }
+        let span = component.fn_ident.span;
+        if !self.config.defer_render {
+            // `document.createElement` throws for elements that gain children
+            // in their constructor
+            emit_error(
+                span,
+                "components without a shadow root need `deferRender` to render on connection",
+            );
+            return false;
+        }
+        if !component.styles.is_empty() && !self.config.style_tags {
+            emit_error(
+                span,
+                "style sheets can only be adopted by a shadow root; set `styleTags` to render `<style>` elements into the light DOM",
+            );
+            return false;
+        }
+        true
+    }
+
+    fn lift(
+        &mut self,
+        m: &mut Module,
+        mut component: Component,
+        types: &TypeDecls,
+        signals: &Signals,
+        elements: &Elements,
+        imports: &mut RuntimeImports,
+    ) {
+        let Some((fn_idx, function)) = find_fn_mut(m, &component.fn_ident) else {
+            return;
+        };
+
+        // children stay in the light DOM and are projected through slots
+        component.slots = lower_slots(function, imports);
+        if !component.slots.is_empty() && component.shadow.is_none() {
+            emit_error(
+                component.fn_ident.span,
+                "slots need a shadow root; components rendering into the light DOM cannot project their children",
+            );
+            return;
+        }
+
+        // 2. back the props with signals and compile the returned JSX into DOM construction
+        let (mut props, store) = lift_props(function, types, &self.comments);
+        for prop in &mut props {
+            prop.reflect |= self.config.reflect.iter().any(|name| *name == *prop.name);
+        }
+        component.props = props;
+        component.events = lower_emits(
+            function,
+            types,
+            &self.comments,
+            imports,
+            self.unresolved_ctxt,
+        );
+
+        let mut lowering = JsxLowering::new(
+            signals.clone(),
+            elements.clone(),
+            store,
+            imports,
+            self.unresolved_ctxt,
+            self.config.codegen,
+            self.config.delegate_events,
+        );
+        function.visit_mut_with(&mut lowering);
+        let hoisted = lowering.take_hoisted();
+        component.delegated_events = lowering.take_delegated();
+        let needs = lifecycle::needs(function, imports);
+        component.form_associated |= needs.form_associated;
+        component.lifecycle = needs.lifecycle || component.form_associated;
+
+        // 3. class <Fn>Element extends HTMLElement { ... } + customElements.define(...)
+        let mut items = define_element(&component, &self.config, imports, self.unresolved_ctxt);
+
+        // 4. the class takes over the export of the function
+        let class = component.class_ident.clone();
+        match component.export {
+            Export::Default => items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
+                ExportDefaultExpr {
+                    span: DUMMY_SP,
+                    expr: Box::new(class.into()),
+                },
+            ))),
+            // export { IconElement as Icon };
+            Export::Named => items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+                NamedExport {
+                    span: DUMMY_SP,
+                    specifiers: vec![ExportSpecifier::Named(ExportNamedSpecifier {
+                        span: DUMMY_SP,
+                        orig: ModuleExportName::Ident(class),
+                        exported: Some(ModuleExportName::Ident(
+                            IdentName::from(component.fn_ident.sym.clone()).into(),
+                        )),
+                        is_type_only: false,
+                    })],
+                    src: None,
+                    type_only: false,
+                    with: None,
+                },
+            ))),
+            Export::None => {}
+        }
+
+        // `export default Counter;` is replaced in place, everything else goes after the function
+        let class_idx = match default_export_of(m, &component.fn_ident) {
+            Some(idx) => {
+                m.body.remove(idx);
+                idx
+            }
+            None => fn_idx + 1,
+        };
+        m.body.splice(class_idx..class_idx, items);
+
+        // templates must exist before `define` upgrades elements already in the document
+        let hoist_idx = fn_idx.min(class_idx);
+        m.body.splice(
+            hoist_idx..hoist_idx,
+            hoisted.into_iter().map(ModuleItem::Stmt),
+        );
+        self.lifted.push(component);
+    }
+}
+
+/// Reports invalid and duplicate tag names at the component functions.
+fn validate_tag_names(components: &[Component]) -> bool {
+    let mut valid = true;
+    let mut error = |component: &Component, message: &str| {
+        emit_error(component.fn_ident.span, message);
+        valid = false;
+    };
+
+    for (i, component) in components.iter().enumerate() {
+        if let Err(message) = validate_tag_name(&component.tag_name) {
+            error(component, &message);
+        } else if components[..i]
+            .iter()
+            .any(|other| other.tag_name == component.tag_name)
+        {
+            let message = format!(
+                "`{}` is already defined by another component of this module",
+                component.tag_name
+            );
+            error(component, &message);
+        }
+    }
+
+    valid
+}
+
+/// Index of `export default <ident>;`.
+fn default_export_of(m: &Module, ident: &Ident) -> Option<usize> {
+    m.body.iter().position(|item| {
+        matches!(
+            item,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr { expr, .. }))
+                if expr.as_ident().is_some_and(|e| e.to_id() == ident.to_id())
+        )
+    })
+}
+
+/// Finds the top-level function declaration bound to `ident`.
+fn find_fn_mut<'a>(m: &'a mut Module, ident: &Ident) -> Option<(usize, &'a mut Function)> {
+    m.body.iter_mut().enumerate().find_map(|(i, item)| {
+        let decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &mut export.decl,
+            _ => return None,
+        };
+        match decl {
+            Decl::Fn(f) if f.ident.to_id() == ident.to_id() => Some((i, &mut *f.function)),
+            _ => None,
+        }
+    })
+}
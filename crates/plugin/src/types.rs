@@ -0,0 +1,213 @@
+//! TypeScript prop annotations and the attribute conversions they imply.
+//!
+//! Attributes are always strings, so the type a prop is annotated with decides
+//! how `attributeChangedCallback` parses it:
+//!
+//! ```ts
+//! interface CounterProps { initial?: number; open: boolean; items: string[] }
+//! // initial -> Number(value), open -> value !== null, items -> JSON.parse(value)
+//! ```
+//!
+//! Members tagged `@reflect` are also written back to their attribute.
+
+use std::collections::HashMap;
+
+use swc_core::common::Spanned;
+use swc_core::common::comments::Comments;
+use swc_core::ecma::ast::*;
+use swc_core::ecma::atoms::Atom;
+use swc_core::ecma::codegen::to_code;
+
+use crate::jsdoc;
+
+/// How an attribute value is converted before it is stored in the prop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum PropKind {
+    /// Passed through unchanged.
+    #[default]
+    String,
+    /// `Number(value)`
+    Number,
+    /// Presence of the attribute.
+    Boolean,
+    /// `JSON.parse(value)`
+    Json,
+}
+
+/// What the props type says about a single prop.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PropType {
+    pub kind: PropKind,
+    /// Tagged `@reflect`.
+    pub reflect: bool,
+    /// The annotation as written, e.g. `number | null`.
+    pub ty: Option<String>,
+    /// JSDoc of the member.
+    pub description: Option<String>,
+}
+
+/// Type declarations of a module, keyed by their binding.
+pub(crate) struct TypeDecls {
+    interfaces: HashMap<Id, Vec<TsTypeElement>>,
+    aliases: HashMap<Id, Box<TsType>>,
+}
+
+impl TypeDecls {
+    /// Collects the top-level interfaces and type aliases of `m`.
+    pub fn from_module(m: &Module) -> Self {
+        let mut interfaces = HashMap::new();
+        let mut aliases = HashMap::new();
+
+        for item in &m.body {
+            let decl = match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+                _ => continue,
+            };
+            match decl {
+                // interfaces merge across declarations
+                Decl::TsInterface(interface) => interfaces
+                    .entry(interface.id.to_id())
+                    .or_insert_with(Vec::new)
+                    .extend(interface.body.body.iter().cloned()),
+                Decl::TsTypeAlias(alias) => {
+                    aliases.insert(alias.id.to_id(), alias.type_ann.clone());
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            interfaces,
+            aliases,
+        }
+    }
+
+    /// Types of the members of the props type `ann`, e.g. `CounterProps` or
+    /// `{ initial?: number }`.
+    pub fn prop_types(&self, ann: &TsType, comments: &dyn Comments) -> HashMap<Atom, PropType> {
+        self.members(ann, 0)
+            .into_iter()
+            .flatten()
+            .filter_map(|member| {
+                let TsTypeElement::TsPropertySignature(prop) = member else {
+                    return None;
+                };
+                let name = match &*prop.key {
+                    Expr::Ident(ident) => ident.sym.clone(),
+                    Expr::Lit(Lit::Str(str)) => str.value.clone(),
+                    _ => return None,
+                };
+                let kind = prop
+                    .type_ann
+                    .as_ref()
+                    .map_or(PropKind::String, |ann| self.kind(&ann.type_ann, 0));
+                let reflect = jsdoc::tag(comments, prop.span_lo(), "reflect").is_some();
+                let ty = prop.type_ann.as_ref().map(|ann| to_code(&ann.type_ann));
+                let description = jsdoc::description(comments, prop.span_lo());
+                Some((
+                    name,
+                    PropType {
+                        kind,
+                        reflect,
+                        ty,
+                        description,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    pub fn members<'a>(&'a self, ty: &'a TsType, depth: usize) -> Option<Vec<&'a TsTypeElement>> {
+        match ty {
+            TsType::TsTypeLit(lit) => Some(lit.members.iter().collect()),
+            TsType::TsParenthesizedType(paren) => self.members(&paren.type_ann, depth),
+            // A & B
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(
+                intersection,
+            )) => Some(
+                intersection
+                    .types
+                    .iter()
+                    .filter_map(|ty| self.members(ty, depth))
+                    .flatten()
+                    .collect(),
+            ),
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(name),
+                ..
+            }) => {
+                if let Some(members) = self.interfaces.get(&name.to_id()) {
+                    return Some(members.iter().collect());
+                }
+                let alias = self.aliases.get(&name.to_id())?;
+                // guards against `type A = B; type B = A;`
+                (depth < MAX_DEPTH).then(|| self.members(alias, depth + 1))?
+            }
+            _ => None,
+        }
+    }
+
+    fn kind(&self, ty: &TsType, depth: usize) -> PropKind {
+        match ty {
+            TsType::TsKeywordType(keyword) => match keyword.kind {
+                TsKeywordTypeKind::TsNumberKeyword => PropKind::Number,
+                TsKeywordTypeKind::TsBooleanKeyword => PropKind::Boolean,
+                TsKeywordTypeKind::TsObjectKeyword => PropKind::Json,
+                _ => PropKind::String,
+            },
+            TsType::TsLitType(lit) => match lit.lit {
+                TsLit::Number(_) => PropKind::Number,
+                TsLit::Bool(_) => PropKind::Boolean,
+                _ => PropKind::String,
+            },
+            TsType::TsArrayType(_) | TsType::TsTupleType(_) | TsType::TsTypeLit(_) => {
+                PropKind::Json
+            }
+            TsType::TsParenthesizedType(paren) => self.kind(&paren.type_ann, depth),
+            TsType::TsTypeOperator(op) => self.kind(&op.type_ann, depth),
+            // "sm" | "md" | undefined, 1 | 2, true | false
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+                let mut kinds = union
+                    .types
+                    .iter()
+                    .filter(|ty| !is_nullish(ty))
+                    .map(|ty| self.kind(ty, depth));
+                let first = kinds.next().unwrap_or_default();
+                if kinds.all(|kind| kind == first) {
+                    first
+                } else {
+                    PropKind::String
+                }
+            }
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(name),
+                ..
+            }) => {
+                if self.interfaces.contains_key(&name.to_id())
+                    || matches!(&*name.sym, "Array" | "ReadonlyArray" | "Record")
+                {
+                    return PropKind::Json;
+                }
+                match self.aliases.get(&name.to_id()) {
+                    Some(alias) if depth < MAX_DEPTH => self.kind(alias, depth + 1),
+                    _ => PropKind::String,
+                }
+            }
+            _ => PropKind::String,
+        }
+    }
+}
+
+/// How many type aliases are followed before giving up.
+const MAX_DEPTH: usize = 16;
+
+fn is_nullish(ty: &TsType) -> bool {
+    matches!(
+        ty,
+        TsType::TsKeywordType(TsKeywordType {
+            kind: TsKeywordTypeKind::TsUndefinedKeyword | TsKeywordTypeKind::TsNullKeyword,
+            ..
+        })
+    )
+}
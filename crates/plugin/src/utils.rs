@@ -0,0 +1,167 @@
+//! Small AST factories shared by the code generators.
+
+use swc_core::common::errors::HANDLER;
+use swc_core::common::{DUMMY_SP, Span, SyntaxContext};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::utils::ExprFactory;
+
+/// Reports a compile error at `span`.
+pub(crate) fn emit_error(span: Span, message: &str) {
+    HANDLER.with(|handler| handler.struct_span_err(span, message).emit());
+}
+
+/// Reports a compile warning at `span`.
+pub(crate) fn emit_warning(span: Span, message: &str) {
+    HANDLER.with(|handler| handler.struct_span_warn(span, message).emit());
+}
+
+/// Identifier in the given syntax context.
+pub(crate) fn ident(ctxt: SyntaxContext, sym: &str) -> Ident {
+    Ident::new(sym.into(), DUMMY_SP, ctxt)
+}
+
+/// String literal expression.
+pub(crate) fn str_lit(value: &str) -> Expr {
+    Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    }))
+}
+
+/// `obj.prop`
+pub(crate) fn member(obj: impl Into<Box<Expr>>, prop: &str) -> Expr {
+    let obj: Box<Expr> = obj.into();
+    obj.make_member(IdentName::from(prop)).into()
+}
+
+/// `callee(args...)`
+pub(crate) fn call(callee: impl Into<Box<Expr>>, args: Vec<Expr>) -> Expr {
+    let callee: Box<Expr> = callee.into();
+    callee.as_call(
+        DUMMY_SP,
+        args.into_iter().map(ExprFactory::as_arg).collect(),
+    )
+}
+
+/// `const name = init;`
+pub(crate) fn const_decl(name: Ident, init: Expr) -> Stmt {
+    Box::new(init)
+        .into_var_decl(VarDeclKind::Const, name.into())
+        .into()
+}
+
+/// `{ key: value, ... }` with plain identifier keys.
+pub(crate) fn object(props: Vec<(&str, Expr)>) -> Expr {
+    Expr::Object(ObjectLit {
+        span: DUMMY_SP,
+        props: props
+            .into_iter()
+            .map(|(key, value)| {
+                PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(key.into()),
+                    value: Box::new(value),
+                })))
+            })
+            .collect(),
+    })
+}
+
+/// `!expr`
+pub(crate) fn not(expr: Expr) -> Expr {
+    Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: op!("!"),
+        arg: Box::new(expr),
+    })
+}
+
+/// `left = right`
+pub(crate) fn assign(left: Expr, right: Expr) -> Expr {
+    let Expr::Member(left) = left else {
+        unreachable!("assignment targets are member expressions");
+    };
+    Expr::Assign(AssignExpr {
+        span: DUMMY_SP,
+        op: op!("="),
+        left: left.into(),
+        right: Box::new(right),
+    })
+}
+
+/// Class method or accessor with the given body.
+pub(crate) fn method(
+    kind: MethodKind,
+    key: &str,
+    is_static: bool,
+    params: Vec<Pat>,
+    stmts: Vec<Stmt>,
+) -> ClassMember {
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        key: PropName::Ident(key.into()),
+        function: Box::new(Function {
+            params: params.into_iter().map(Param::from).collect(),
+            body: Some(block(stmts)),
+            ..Default::default()
+        }),
+        kind,
+        is_static,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    })
+}
+
+/// `{ stmts... }`
+pub(crate) fn block(stmts: Vec<Stmt>) -> BlockStmt {
+    BlockStmt {
+        stmts,
+        ..Default::default()
+    }
+}
+
+/// `kebab-case` / `snake_case` -> `PascalCase`
+pub(crate) fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// `camelCase` / `PascalCase` -> `kebab-case`, keeping acronyms together:
+/// `HTMLView` -> `html-view`, `Vec3D` -> `vec3-d`.
+pub(crate) fn kebab_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut kebab = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_uppercase() {
+            kebab.push(c);
+            continue;
+        }
+
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        // a word starts after a lowercase letter or digit, or at the last
+        // capital of an acronym followed by a lowercase letter
+        let word_start = prev.is_some_and(|prev| {
+            prev.is_lowercase()
+                || prev.is_numeric()
+                || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+        });
+        if word_start {
+            kebab.push('-');
+        }
+        kebab.extend(c.to_lowercase());
+    }
+
+    kebab
+}
@@ -0,0 +1,157 @@
+use serde_json::json;
+use swc_plugin_fluxel::Config;
+use swc_plugin_fluxel::manifest::{Error, analyze};
+
+fn manifest(path: &str, src: &str) -> serde_json::Value {
+    let module = analyze(path, src.to_string(), &Config::default())
+        .unwrap()
+        .expect("the module defines elements");
+    serde_json::to_value(module).unwrap()
+}
+
+#[test]
+fn describes_lifted_elements() {
+    let module = manifest(
+        "src/stepper.tsx",
+        r#"
+import { defineEvents, Slot } from "@fluxel/core";
+interface StepperProps {
+  /** Value the stepper starts at. */
+  initial?: number;
+  /** @reflect */
+  label: string;
+}
+/** A number input with buttons. */
+export default function Stepper({ initial = 0, label, children }: StepperProps) {
+  const emit = defineEvents<{
+    /** The value changed. */
+    change: number;
+    reset: void;
+  }>();
+  return (
+    <div>
+      <Slot name="prefix" />
+      <button onClick={() => emit("change", 1)}>{label}</button>
+      {children}
+    </div>
+  );
+}
+"#,
+    );
+
+    let class = json!({ "name": "StepperElement", "module": "src/stepper.tsx" });
+    assert_eq!(
+        module,
+        json!({
+            "kind": "javascript-module",
+            "path": "src/stepper.tsx",
+            "declarations": [{
+                "kind": "class",
+                "name": "StepperElement",
+                "description": "A number input with buttons.",
+                "superclass": { "name": "HTMLElement", "package": "global:" },
+                "customElement": true,
+                "tagName": "fluxel-stepper",
+                "attributes": [
+                    {
+                        "name": "initial",
+                        "fieldName": "initial",
+                        "type": { "text": "number" },
+                        "default": "0",
+                        "description": "Value the stepper starts at."
+                    },
+                    { "name": "label", "fieldName": "label", "type": { "text": "string" } }
+                ],
+                "members": [
+                    {
+                        "kind": "field",
+                        "name": "initial",
+                        "type": { "text": "number" },
+                        "default": "0",
+                        "description": "Value the stepper starts at.",
+                        "attribute": "initial"
+                    },
+                    {
+                        "kind": "field",
+                        "name": "label",
+                        "type": { "text": "string" },
+                        "attribute": "label",
+                        "reflects": true
+                    }
+                ],
+                "events": [
+                    {
+                        "name": "change",
+                        "type": { "text": "CustomEvent<number>" },
+                        "description": "The value changed."
+                    },
+                    { "name": "reset", "type": { "text": "CustomEvent" } }
+                ],
+                "slots": [{ "name": "prefix" }, { "name": "" }]
+            }],
+            "exports": [
+                { "kind": "js", "name": "default", "declaration": class },
+                {
+                    "kind": "custom-element-definition",
+                    "name": "fluxel-stepper",
+                    "declaration": class
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn names_anonymous_elements_after_the_module() {
+    let module = manifest(
+        "date-picker.jsx",
+        r#"
+export default () => <input type="date" />;
+"#,
+    );
+
+    assert_eq!(module["declarations"][0]["tagName"], "fluxel-date-picker");
+    assert_eq!(module["declarations"][0]["name"], "DatePickerElement");
+}
+
+#[test]
+fn skips_modules_without_components() {
+    let module = analyze(
+        "util.ts",
+        "export const answer = 42;".into(),
+        &Config::default(),
+    );
+
+    assert!(matches!(module, Ok(None)));
+}
+
+#[test]
+fn skips_story_files() {
+    for src in [
+        r#"
+import { Button } from "./button";
+export default { title: "Button", component: Button };
+export const Primary = { render: () => <Button label="Save" /> };
+"#,
+        r#"
+const meta = { title: "Card", render: () => <p>Card</p> };
+export default meta;
+"#,
+    ] {
+        let module = analyze("card.stories.tsx", src.into(), &Config::default());
+        assert!(matches!(module, Ok(None)), "{module:?}");
+    }
+}
+
+#[test]
+fn reports_diagnostics() {
+    let error = analyze(
+        "icon.tsx",
+        "export default function Icon( {".into(),
+        &Config::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Diagnostics { .. }));
+    assert!(error.to_string().starts_with("icon.tsx:"), "{error}");
+}
@@ -1,9 +1,3144 @@
-use swc_core::ecma::{transforms::testing::test, visit::visit_mut_pass};
-use swc_plugin_fluxel;
-
-test!(
-    Default::default(),
-    |_| visit_mut_pass(swc_plugin_fluxel::FluxelTransform),
-    boo,
-    r#"foo === bar;"#
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use swc_core::common::errors::{HANDLER, Handler};
+use swc_core::common::{
+    FileName, GLOBALS, Mark, SourceMap, comments::SingleThreadedComments, sync::Lrc,
+};
+use swc_core::ecma::{
+    ast::{EsVersion, Pass, Program},
+    parser::{Syntax, TsSyntax, parse_file_as_module, parse_file_as_script},
+    transforms::{
+        base::resolver,
+        testing::{Tester, test_inline},
+    },
+    visit::visit_mut_pass,
+};
+use swc_plugin_fluxel::{Codegen, Config, FluxelTransform, ShadowMode};
+
+fn tsx() -> Syntax {
+    Syntax::Typescript(TsSyntax {
+        tsx: true,
+        ..Default::default()
+    })
+}
+
+fn fluxel(t: &Tester) -> impl Pass + use<> {
+    fluxel_with(t, Config::default())
+}
+
+fn fluxel_with(t: &Tester, config: Config) -> impl Pass + use<> {
+    fluxel_in(t, config, None)
+}
+
+fn fluxel_in(t: &Tester, config: Config, filename: Option<&str>) -> impl Pass + use<> {
+    let unresolved_mark = Mark::new();
+    let top_level_mark = Mark::new();
+
+    (
+        resolver(unresolved_mark, top_level_mark, true),
+        visit_mut_pass(
+            FluxelTransform::new(config, unresolved_mark, t.comments.clone())
+                .with_filename(filename.map(String::from)),
+        ),
+    )
+}
+
+/// Runs the transform over `src` and returns the diagnostics it emitted.
+fn transform_errors(config: Config, src: &str) -> String {
+    swc_core::testing::run_test(false, |cm, handler| {
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+        let comments = SingleThreadedComments::default();
+        let module = parse_file_as_module(
+            &fm,
+            tsx(),
+            EsVersion::latest(),
+            Some(&comments),
+            &mut vec![],
+        )
+        .unwrap();
+        let unresolved_mark = Mark::new();
+        Program::Module(module).apply((
+            resolver(unresolved_mark, Mark::new(), true),
+            visit_mut_pass(FluxelTransform::new(config, unresolved_mark, comments)),
+        ));
+
+        if handler.has_errors() {
+            Err(())
+        } else {
+            Ok(())
+        }
+    })
+    .unwrap_err()
+    .to_string()
+}
+
+/// Like [`transform_errors`], with warnings, which the test handler drops.
+fn transform_warnings(config: Config, src: &str) -> String {
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let buffer = Buffer::default();
+    GLOBALS.set(&Default::default(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let handler = Handler::with_emitter_writer(Box::new(buffer.clone()), Some(cm.clone()));
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+        let comments = SingleThreadedComments::default();
+        let module = parse_file_as_module(
+            &fm,
+            tsx(),
+            EsVersion::latest(),
+            Some(&comments),
+            &mut vec![],
+        )
+        .unwrap();
+        let unresolved_mark = Mark::new();
+        HANDLER.set(&handler, || {
+            Program::Module(module).apply((
+                resolver(unresolved_mark, Mark::new(), true),
+                visit_mut_pass(FluxelTransform::new(config, unresolved_mark, comments)),
+            ))
+        });
+    });
+    let output = buffer.0.lock().unwrap();
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    lifts_default_exported_function,
+    r#"
+/** @customElement */
+export default function Counter() {
+  return null;
+}
+"#,
+    r#"
+function Counter() {
+  return null;
+}
+class CounterElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Counter(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-counter", CounterElement);
+export default CounterElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    lifts_default_exported_identifier,
+    r#"
+/** @customElement */
+function FancyButton() {
+  return null;
+}
+export default FancyButton;
+"#,
+    r#"
+function FancyButton() {
+  return null;
+}
+class FancyButtonElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = FancyButton(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-fancy-button", FancyButtonElement);
+export default FancyButtonElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_in(t, Config::default(), Some("src/components/date-picker.tsx")),
+    names_anonymous_function_after_file,
+    r#"
+/** @customElement */
+export default function () {
+  return null;
+}
+"#,
+    r#"
+function DatePicker() {
+  return null;
+}
+class DatePickerElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = DatePicker(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-date-picker", DatePickerElement);
+export default DatePickerElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_in(t, Config::default(), Some("/app/user_card.stories.tsx")),
+    names_anonymous_arrow_after_file,
+    r#"
+/** @customElement */
+export default ({ name }) => name;
+"#,
+    r#"
+import { signal } from "@fluxel/core";
+function UserCard(__props) {
+  return __props.name.value;
+}
+class UserCardElement extends HTMLElement {
+  static observedAttributes = ["name"];
+  #props = { name: signal() };
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["name"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = UserCard(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "name":
+        this.#props.name.value = value;
+        break;
+    }
+  }
+  get name() {
+    return this.#props.name.value;
+  }
+  set name(value) {
+    this.#props.name.value = value;
+  }
+}
+customElements.define("fluxel-user-card", UserCardElement);
+export default UserCardElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    keeps_acronyms_together_in_names,
+    r#"
+/** @customElement */
+export default function HTMLView({ maxHTTPRetries }: { maxHTTPRetries: number }) {
+  return null;
+}
+"#,
+    r#"
+import { signal } from "@fluxel/core";
+function HTMLView(__props) {
+  return null;
+}
+class HTMLViewElement extends HTMLElement {
+  static observedAttributes = ["max-http-retries"];
+  #props = { maxHTTPRetries: signal() };
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["maxHTTPRetries"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = HTMLView(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "max-http-retries":
+        this.#props.maxHTTPRetries.value = value === null ? null : Number(value);
+        break;
+    }
+  }
+  get maxHTTPRetries() {
+    return this.#props.maxHTTPRetries.value;
+  }
+  set maxHTTPRetries(value) {
+    this.#props.maxHTTPRetries.value = value;
+  }
+}
+customElements.define("fluxel-html-view", HTMLViewElement);
+export default HTMLViewElement;
+"#
+);
+
+#[test]
+fn reports_reserved_tag_names() {
+    let config = Config {
+        tag_prefix: "font".into(),
+        ..Default::default()
+    };
+    let errors = transform_errors(
+        config,
+        "/** @customElement */ export default function Face() {}",
+    );
+
+    assert!(
+        errors.contains("`font-face` is reserved by SVG and MathML"),
+        "{errors}"
+    );
+    assert!(
+        errors.contains("export default function Face() {}"),
+        "{errors}"
+    );
+}
+
+#[test]
+fn reports_invalid_tag_names() {
+    let config = Config {
+        tag_prefix: "Acme".into(),
+        ..Default::default()
+    };
+    let errors = transform_errors(
+        config,
+        "/** @customElement */ export default function Button() {}",
+    );
+
+    assert!(
+        errors.contains("`Acme-button` is not a valid custom element name"),
+        "{errors}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    lifts_const_bound_arrow,
+    r#"
+const Counter = ({ initial = 0 }: { initial?: number }) => <p>{initial}</p>;
+export default Counter;
+"#,
+    r#"
+import { insert, signal, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Counter(__props) {
+  const _p = document.createElement("p");
+  _p.append(insert(() => __props.initial.value ?? 0));
+  return _p;
+}
+class CounterElement extends HTMLElement {
+  static observedAttributes = ["initial"];
+  #props = { initial: signal() };
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["initial"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Counter(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "initial":
+        this.#props.initial.value = value === null ? null : Number(value);
+        break;
+    }
+  }
+  get initial() {
+    return this.#props.initial.value;
+  }
+  set initial(value) {
+    this.#props.initial.value = value;
+  }
+}
+customElements.define("fluxel-counter", CounterElement);
+export default CounterElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    lifts_memo_wrapped_function_expression,
+    r#"
+import { memo } from "./compat";
+
+/** @customElement */
+export default memo(function Greeting() {
+  return null;
+});
+"#,
+    r#"
+import { memo } from "./compat";
+function Greeting() {
+  return null;
+}
+class GreetingElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Greeting(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-greeting", GreetingElement);
+export default GreetingElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    leaves_story_files_alone,
+    r#"
+import { Button } from "./button";
+
+export default { title: "Button", component: Button };
+
+const meta = { render: () => <Button label="Save" /> };
+export const Primary = meta;
+"#,
+    r#"
+import { Button } from "./button";
+export default { title: "Button", component: Button };
+const meta = { render: () => <Button label="Save" /> };
+export const Primary = meta;
+"#
+);
+
+#[test]
+fn reports_non_function_default_exports() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+const view = <p />;
+/** @customElement */
+export default view;
+"#,
+    );
+    assert!(
+        errors.contains("`view` is default-exported but is not a function component"),
+        "{errors}"
+    );
+
+    let errors = transform_errors(
+        Config::default(),
+        "/** @customElement */ export default { view: <p /> };",
+    );
+    assert!(
+        errors.contains("default export is not a function component"),
+        "{errors}"
+    );
+
+    let errors = transform_errors(Config::default(), "export default () => <p />;");
+    assert!(
+        errors.contains("anonymous components need a name"),
+        "{errors}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    lifts_marked_named_exports,
+    r#"
+import { defineElement } from "@fluxel/core";
+
+/** @customElement acme-star-icon */
+export function StarIcon() {
+  return null;
+}
+
+export const HeartIcon = defineElement(() => null);
+
+export const CloseIcon = defineElement("acme-close", function () {
+  return null;
+});
+
+export function helper() {}
+"#,
+    r#"
+import { defineElement } from "@fluxel/core";
+/** @customElement acme-star-icon */ function StarIcon() {
+  return null;
+}
+class StarIconElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = StarIcon(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("acme-star-icon", StarIconElement);
+export { StarIconElement as StarIcon };
+function HeartIcon() {
+  return null;
+}
+class HeartIconElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = HeartIcon(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-heart-icon", HeartIconElement);
+export { HeartIconElement as HeartIcon };
+function CloseIcon() {
+  return null;
+}
+class CloseIconElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = CloseIcon(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("acme-close", CloseIconElement);
+export { CloseIconElement as CloseIcon };
+export function helper() {}
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    creates_imported_components_at_runtime,
+    r#"
+import { StarIcon } from "./icons";
+import * as icons from "./icons";
+
+export default function Rating() {
+  return (
+    <p>
+      <StarIcon size={2} />
+      <icons.HeartIcon />
+    </p>
+  );
+}
+"#,
+    r#"
+import { createComponent } from "@fluxel/core";
+import { StarIcon } from "./icons";
+import * as icons from "./icons";
+function Rating() {
+  const _p = document.createElement("p");
+  _p.append(createComponent(StarIcon, { size: 2 }), createComponent(icons.HeartIcon, {}));
+  return _p;
+}
+class RatingElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Rating(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-rating", RatingElement);
+export default RatingElement;
+"#
+);
+
+#[test]
+fn reports_duplicate_tag_names() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+/** @customElement fluxel-icon */
+export function Star() {}
+/** @customElement fluxel-icon */
+export function Heart() {}
+"#,
+    );
+
+    assert!(
+        errors.contains("`fluxel-icon` is already defined by another component"),
+        "{errors}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    observes_props_as_attributes,
+    r#"
+interface GreetingProps {
+  initialName: string;
+  punctuation?: string;
+}
+
+/** @customElement */
+export default function Greeting({ initialName, punctuation: mark = "!" }: GreetingProps) {
+  const text = `Hello ${initialName}${mark}`;
+  return null;
+}
+"#,
+    r#"
+import { signal } from "@fluxel/core";
+interface GreetingProps {
+  initialName: string;
+  punctuation?: string;
+}
+function Greeting(__props) {
+  const text = `Hello ${__props.initialName.value}${__props.punctuation.value ?? "!"}`;
+  return null;
+}
+class GreetingElement extends HTMLElement {
+  static observedAttributes = ["initial-name", "punctuation"];
+  #props = { initialName: signal(), punctuation: signal() };
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["initialName", "punctuation"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Greeting(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "initial-name":
+        this.#props.initialName.value = value;
+        break;
+      case "punctuation":
+        this.#props.punctuation.value = value;
+        break;
+    }
+  }
+  get initialName() {
+    return this.#props.initialName.value;
+  }
+  set initialName(value) {
+    this.#props.initialName.value = value;
+  }
+  get punctuation() {
+    return this.#props.punctuation.value;
+  }
+  set punctuation(value) {
+    this.#props.punctuation.value = value;
+  }
+}
+customElements.define("fluxel-greeting", GreetingElement);
+export default GreetingElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    coerces_attributes_by_prop_type,
+    r#"
+type Size = "sm" | "md" | "lg";
+
+export interface BadgeProps {
+  count?: number;
+  open: boolean;
+  size: Size;
+  tags: string[];
+}
+
+/** @customElement */
+export default function Badge(props: BadgeProps & { meta: { id: string } }) {
+  return [props.count, props.open, props.size, props.tags, props.meta];
+}
+"#,
+    r#"
+import { signal } from "@fluxel/core";
+type Size = "sm" | "md" | "lg";
+export interface BadgeProps {
+  count?: number;
+  open: boolean;
+  size: Size;
+  tags: string[];
+}
+function Badge(props) {
+  return [props.count.value, props.open.value, props.size.value, props.tags.value, props.meta.value];
+}
+class BadgeElement extends HTMLElement {
+  static observedAttributes = ["count", "open", "size", "tags", "meta"];
+  #props = { count: signal(), open: signal(), size: signal(), tags: signal(), meta: signal() };
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["count", "open", "size", "tags", "meta"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Badge(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "count":
+        this.#props.count.value = value === null ? null : Number(value);
+        break;
+      case "open":
+        this.#props.open.value = value !== null;
+        break;
+      case "size":
+        this.#props.size.value = value;
+        break;
+      case "tags":
+        this.#props.tags.value = value === null ? null : JSON.parse(value);
+        break;
+      case "meta":
+        this.#props.meta.value = value === null ? null : JSON.parse(value);
+        break;
+    }
+  }
+  get count() {
+    return this.#props.count.value;
+  }
+  set count(value) {
+    this.#props.count.value = value;
+  }
+  get open() {
+    return this.#props.open.value;
+  }
+  set open(value) {
+    this.#props.open.value = value;
+  }
+  get size() {
+    return this.#props.size.value;
+  }
+  set size(value) {
+    this.#props.size.value = value;
+  }
+  get tags() {
+    return this.#props.tags.value;
+  }
+  set tags(value) {
+    this.#props.tags.value = value;
+  }
+  get meta() {
+    return this.#props.meta.value;
+  }
+  set meta(value) {
+    this.#props.meta.value = value;
+  }
+}
+customElements.define("fluxel-badge", BadgeElement);
+export default BadgeElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            reflect: vec!["variant".into()],
+            ..Default::default()
+        }
+    ),
+    reflects_tagged_props,
+    r#"
+interface ButtonProps {
+  /** @reflect */
+  disabled?: boolean;
+  variant?: "primary" | "ghost";
+  label: string;
+}
+
+/** @customElement */
+export default function Button({ disabled, variant, label }: ButtonProps) {
+  return null;
+}
+"#,
+    r#"
+import { signal, effect } from "@fluxel/core";
+interface ButtonProps {
+  /** @reflect */ disabled?: boolean;
+  variant?: "primary" | "ghost";
+  label: string;
+}
+function Button(__props) {
+  return null;
+}
+class ButtonElement extends HTMLElement {
+  static observedAttributes = ["disabled", "variant", "label"];
+  #props = { disabled: signal(), variant: signal(), label: signal() };
+  #reflecting = false;
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["disabled", "variant", "label"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Button(this.#props);
+      this.#root.appendChild(_n);
+      effect(() => {
+        const value = this.#props.disabled.value;
+        if (value !== undefined) {
+          this.#reflecting = true;
+          this.toggleAttribute("disabled", !!value);
+          this.#reflecting = false;
+        }
+      });
+      effect(() => {
+        const value = this.#props.variant.value;
+        if (value !== undefined) {
+          this.#reflecting = true;
+          if (value === null) this.removeAttribute("variant");
+          else this.setAttribute("variant", String(value));
+          this.#reflecting = false;
+        }
+      });
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    if (this.#reflecting) return;
+    switch (name) {
+      case "disabled":
+        this.#props.disabled.value = value !== null;
+        break;
+      case "variant":
+        this.#props.variant.value = value;
+        break;
+      case "label":
+        this.#props.label.value = value;
+        break;
+    }
+  }
+  get disabled() {
+    return this.#props.disabled.value;
+  }
+  set disabled(value) {
+    this.#props.disabled.value = value;
+  }
+  get variant() {
+    return this.#props.variant.value;
+  }
+  set variant(value) {
+    this.#props.variant.value = value;
+  }
+  get label() {
+    return this.#props.label.value;
+  }
+  set label(value) {
+    this.#props.label.value = value;
+  }
+}
+customElements.define("fluxel-button", ButtonElement);
+export default ButtonElement;
+"#
+);
+
+#[test]
+fn parses_config_json() {
+    let config = Config::from_json(
+        r#"{ "tagPrefix": "acme", "shadowMode": "closed", "codegen": "template", "dev": true }"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.tag_prefix, "acme");
+    assert_eq!(config.shadow_mode, ShadowMode::Closed);
+    assert_eq!(config.codegen, Codegen::Template);
+    assert!(config.dev);
+    assert_eq!(config.jsx_import_source, "@fluxel/core");
+}
+
+#[test]
+fn rejects_invalid_config_json() {
+    let unknown = Config::from_json(r#"{ "prefix": "acme" }"#).unwrap_err();
+    assert!(unknown.to_string().starts_with("unknown field `prefix`"));
+
+    let mistyped = Config::from_json(r#"{ "dev": "yes" }"#).unwrap_err();
+    assert!(
+        mistyped
+            .to_string()
+            .starts_with("invalid type: string \"yes\"")
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| {
+        fluxel_with(
+        t,
+        Config::from_json(
+            r#"{ "tagPrefix": "acme", "shadowMode": "closed", "dev": true, "jsxImportSource": "@acme/runtime" }"#
+        )
+        .unwrap()
+    )
+    },
+    applies_config,
+    r#"
+/** @customElement */
+export default function Button({ label }) {
+  return null;
+}
+"#,
+    r#"
+import { signal } from "@acme/runtime";
+function Button(__props) {
+  return null;
+}
+class ButtonElement extends HTMLElement {
+  static observedAttributes = ["label"];
+  #props = { label: signal() };
+  #root = this.attachShadow({ mode: "closed" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["label"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Button(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "label":
+        this.#props.label.value = value;
+        break;
+    }
+  }
+  get label() {
+    return this.#props.label.value;
+  }
+  set label(value) {
+    this.#props.label.value = value;
+  }
+}
+customElements.get("acme-button") || customElements.define("acme-button", ButtonElement);
+export default ButtonElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    ignores_modules_without_components,
+    r#"
+import { signal } from "@fluxel/core";
+export const count = signal(0);
+export default count;
+foo === bar;
+"#,
+    r#"
+import { signal } from "@fluxel/core";
+export const count = signal(0);
+export default count;
+foo === bar;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    ignores_unmarked_function_declarations,
+    r#"
+function Counter() {}
+foo === bar;
+"#,
+    r#"
+function Counter() {}
+foo === bar;
+"#
+);
+
+#[test]
+fn ignores_scripts() {
+    swc_core::testing::run_test(false, |cm, _| {
+        let src = "/** @customElement */ function Counter() { return <p />; }";
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+        let comments = SingleThreadedComments::default();
+        let script = parse_file_as_script(
+            &fm,
+            tsx(),
+            EsVersion::latest(),
+            Some(&comments),
+            &mut vec![],
+        )
+        .unwrap();
+        let unresolved_mark = Mark::new();
+        let program = Program::Script(script).apply(resolver(unresolved_mark, Mark::new(), true));
+
+        let transformed = program.clone().apply(visit_mut_pass(FluxelTransform::new(
+            Config::default(),
+            unresolved_mark,
+            comments,
+        )));
+
+        assert_eq!(transformed, program);
+        Ok(())
+    })
+    .unwrap();
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    leaves_functions_without_jsx_alone,
+    r#"
+export default function formatDate(d: Date) {
+  return d.toISOString();
+}
+"#,
+    r#"
+export default function formatDate(d: Date) {
+  return d.toISOString();
+}
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_in(t, Config::default(), Some("src/format-date.ts")),
+    leaves_default_exported_helpers_alone,
+    r#"
+const pad = (n: number) => String(n).padStart(2, "0");
+export const format = (d: Date) => `${pad(d.getHours())}:${pad(d.getMinutes())}`;
+export default pad;
+"#,
+    r#"
+const pad = (n: number) => String(n).padStart(2, "0");
+export const format = (d: Date) => `${pad(d.getHours())}:${pad(d.getMinutes())}`;
+export default pad;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_in(t, Config::default(), Some("src/format-date.ts")),
+    leaves_anonymous_helpers_alone,
+    r#"
+export default (d: Date) => d.toISOString();
+"#,
+    r#"
+export default ((d: Date) => d.toISOString());
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    compiles_jsx_to_dom_construction,
+    r#"
+import { signal } from "@fluxel/core";
+
+export default function Counter() {
+  const count = signal(0);
+  return (
+    <div class="counter">
+      <p>Count: {count}</p>
+      <button onclick={() => (count.value += 1)}>+</button>
+    </div>
+  );
+}
+"#,
+    r#"
+import { signal, effect, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Counter() {
+  const count = signal(0);
+  const _div = document.createElement("div");
+  _div.className = "counter";
+  const _p = document.createElement("p");
+  const _txt = document.createTextNode("");
+  effect(() => _txt.data = String(count.value));
+  _p.append("Count: ", _txt);
+  const _button = document.createElement("button");
+  _button.addEventListener("click", () => count.value += 1);
+  _button.textContent = "+";
+  _div.append(_p, _button);
+  return _div;
+}
+class CounterElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Counter(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-counter", CounterElement);
+export default CounterElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    removes_attributes_without_value,
+    r#"
+const label = undefined;
+const hidden = false;
+
+export default function Tooltip() {
+  return <span title={label} aria-hidden={hidden} data-on={true} data-off={false} />;
+}
+"#,
+    r#"
+import { setAttr } from "@fluxel/core";
+const label = undefined;
+const hidden = false;
+function Tooltip() {
+  const _span = document.createElement("span");
+  setAttr(_span, "title", label);
+  setAttr(_span, "aria-hidden", hidden);
+  _span.setAttribute("data-on", "");
+  return _span;
+}
+class TooltipElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Tooltip(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-tooltip", TooltipElement);
+export default TooltipElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    maps_event_handler_names,
+    r#"
+export default function Item() {
+  const select = () => {};
+  return <li onDoubleClick={select} onKeyDown={select} on:item-selected={select} />;
+}
+"#,
+    r#"
+function Item() {
+  const select = () => {};
+  const _li = document.createElement("li");
+  _li.addEventListener("dblclick", select);
+  _li.addEventListener("keydown", select);
+  _li.addEventListener("item-selected", select);
+  return _li;
+}
+class ItemElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Item(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-item", ItemElement);
+export default ItemElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            delegate_events: true,
+            ..Default::default()
+        }
+    ),
+    delegates_bubbling_events,
+    r#"
+export default function Toolbar() {
+  return (
+    <div>
+      <button onClick={() => save()} />
+      <button onClick={() => load()} onFocus={() => track()} />
+      <input onInput={(e) => search(e)} />
+    </div>
+  );
+}
+"#,
+    r#"
+import { delegateEvents } from "@fluxel/core";
+function Toolbar() {
+  const _div = document.createElement("div");
+  const _button = document.createElement("button");
+  _button.$$click = () => save();
+  const _button1 = document.createElement("button");
+  _button1.$$click = () => load();
+  _button1.addEventListener("focus", () => track());
+  const _input = document.createElement("input");
+  _input.$$input = (e) => search(e);
+  _div.append(_button, _button1, _input);
+  return _div;
+}
+class ToolbarElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Toolbar(this.#props);
+      this.#root.appendChild(_n);
+      delegateEvents(this.#root, ["click", "input"]);
+    }
+  }
+}
+customElements.define("fluxel-toolbar", ToolbarElement);
+export default ToolbarElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    binds_signal_reads_in_expressions,
+    r#"
+import { signal, computed } from "@fluxel/core";
+
+const user = signal({ name: "Ada" });
+
+export default function Badge() {
+  const count = signal(1);
+  const double = computed(() => count.value * 2);
+  const label = "Clicks";
+  return (
+    <p>
+      {label.toUpperCase()} {count.value * 2} {user.value.name} {double}
+    </p>
+  );
+}
+"#,
+    r#"
+import { signal, computed, toChildren, effect, insert, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+const user = signal({ name: "Ada" });
+function Badge() {
+  const count = signal(1);
+  const double = computed(() => count.value * 2);
+  const label = "Clicks";
+  const _p = document.createElement("p");
+  const _txt = document.createTextNode("");
+  effect(() => _txt.data = String(count.value * 2));
+  const _txt1 = document.createTextNode("");
+  effect(() => _txt1.data = String(double.value));
+  _p.append(...toChildren(label.toUpperCase()), " ", _txt, " ", insert(() => user.value.name), " ", _txt1);
+  return _p;
+}
+class BadgeElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Badge(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-badge", BadgeElement);
+export default BadgeElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    compiles_control_flow,
+    r#"
+import { signal, Show, For } from "@fluxel/core";
+
+export default function TodoList() {
+  const todos = signal([]);
+  const open = signal(true);
+  return (
+    <section>
+      <Show when={open} fallback={<p>Hidden</p>}>
+        <ul>
+          <For each={todos}>{(todo) => <li>{todo.title}</li>}</For>
+        </ul>
+      </Show>
+      <Show when={todos.value.length === 0}>Nothing to do</Show>
+    </section>
+  );
+}
+"#,
+    r#"
+import { signal, Show, For, toChildren, createFor, createShow, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function TodoList() {
+  const todos = signal([]);
+  const open = signal(true);
+  const _section = document.createElement("section");
+  _section.append(
+    createShow(
+      () => open.value,
+      () => {
+        const _ul = document.createElement("ul");
+        _ul.append(createFor(() => todos.value, (todo) => {
+          const _li = document.createElement("li");
+          _li.append(...toChildren(todo.title));
+          return _li;
+        }));
+        return _ul;
+      },
+      () => {
+        const _p = document.createElement("p");
+        _p.textContent = "Hidden";
+        return _p;
+      }
+    ),
+    createShow(() => todos.value.length === 0, () => {
+      const _frag = document.createDocumentFragment();
+      _frag.append("Nothing to do");
+      return _frag;
+    })
+  );
+  return _section;
+}
+class TodoListElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => TodoList(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-todo-list", TodoListElement);
+export default TodoListElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    diffs_mapped_signals_by_key,
+    r#"
+import { signal } from "@fluxel/core";
+
+export default function Users() {
+  const users = signal([]);
+  const roles = ["admin", "guest"];
+  return (
+    <div>
+      <ul>{users.value.map((user) => <li key={user.id}>{user.name}</li>)}</ul>
+      <select>{roles.map((role) => <option key={role}>{role}</option>)}</select>
+    </div>
+  );
+}
+"#,
+    r#"
+import { signal, toChildren, createFor, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Users() {
+  const users = signal([]);
+  const roles = ["admin", "guest"];
+  const _div = document.createElement("div");
+  const _ul = document.createElement("ul");
+  _ul.append(createFor(() => users.value, (user) => {
+    const _li = document.createElement("li");
+    _li.append(...toChildren(user.name));
+    return _li;
+  }, (user) => user.id));
+  const _select = document.createElement("select");
+  _select.append(...roles.map((role) => {
+    const _option = document.createElement("option");
+    _option.append(...toChildren(role));
+    return _option;
+  }));
+  _div.append(_ul, _select);
+  return _div;
+}
+class UsersElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Users(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-users", UsersElement);
+export default UsersElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    adopts_module_styles,
+    r#"
+import { css } from "@fluxel/core";
+import base from "./base.css?inline";
+
+const styles = css`p { color: tomato; }`;
+
+export default function Note() {
+  return <p>Hi</p>;
+}
+"#,
+    r#"
+import { css } from "@fluxel/core";
+import base from "./base.css?inline";
+const _sheet = new CSSStyleSheet();
+_sheet.replaceSync(base);
+const styles = css`p{color:tomato}`;
+const _sheet1 = new CSSStyleSheet();
+_sheet1.replaceSync(styles);
+function Note() {
+  const _p = document.createElement("p");
+  _p.textContent = "Hi";
+  return _p;
+}
+class NoteElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Note(this.#props);
+      this.#root.adoptedStyleSheets = [_sheet, _sheet1];
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-note", NoteElement);
+export default NoteElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    leaves_css_modules_to_the_bundler,
+    r#"
+import classes from "./note.module.css";
+
+export default function Note() {
+  return <p class={classes.note}>Hi</p>;
+}
+"#,
+    r#"
+import classes from "./note.module.css";
+function Note() {
+  const _p = document.createElement("p");
+  _p.className = classes.note;
+  _p.textContent = "Hi";
+  return _p;
+}
+class NoteElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Note(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-note", NoteElement);
+export default NoteElement;
+"#
+);
+
+#[test]
+fn warns_about_css_imports_without_text() {
+    let warnings = transform_warnings(
+        Config::default(),
+        r#"
+import styles from "./note.css";
+export default function Note() {
+  return <p>Hi</p>;
+}
+"#,
+    );
+
+    assert!(
+        warnings.contains("`./note.css` is not imported as text; add `?inline`"),
+        "{warnings}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            style_tags: true,
+            ..Default::default()
+        }
+    ),
+    falls_back_to_style_tags,
+    r#"
+import styles from "./note.css?inline";
+
+export default function Note() {
+  return <p>Hi</p>;
+}
+"#,
+    r#"
+import styles from "./note.css?inline";
+function Note() {
+  const _p = document.createElement("p");
+  _p.textContent = "Hi";
+  return _p;
+}
+class NoteElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Note(this.#props);
+      const _style = document.createElement("style");
+      _style.textContent = styles;
+      this.#root.append(_style, _n);
+    }
+  }
+}
+customElements.define("fluxel-note", NoteElement);
+export default NoteElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    minifies_static_styles,
+    r#"
+import { css } from "@fluxel/core";
+
+const styles = css`
+  :host {
+    display: block;
+    margin: 0px;
+  }
+  p { color: #ff0000; }
+`;
+
+export default function Note() {
+  return <p>Hi</p>;
+}
+"#,
+    r#"
+import { css } from "@fluxel/core";
+const styles = css`:host{margin:0;display:block}p{color:red}`;
+const _sheet = new CSSStyleSheet();
+_sheet.replaceSync(styles);
+function Note() {
+  const _p = document.createElement("p");
+  _p.textContent = "Hi";
+  return _p;
+}
+class NoteElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Note(this.#props);
+      this.#root.adoptedStyleSheets = [_sheet];
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-note", NoteElement);
+export default NoteElement;
+"#
+);
+
+#[test]
+fn reports_invalid_styles() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+import { css } from "@fluxel/core";
+const styles = css`p { color: red; } }`;
+export default function Note() {
+  return <p>Hi</p>;
+}
+"#,
+    );
+
+    assert!(errors.contains("invalid CSS"), "{errors}");
+}
+
+#[test]
+fn warns_on_selectors_outside_the_shadow_root() {
+    let warnings = transform_warnings(
+        Config::default(),
+        r#"
+import { css } from "@fluxel/core";
+const styles = css`:root { --accent: red; } body p { margin: 0; }`;
+export default function Note() {
+  return <p>Hi</p>;
+}
+"#,
+    );
+
+    assert!(
+        warnings.contains("`:root` never matches inside a shadow root"),
+        "{warnings}"
+    );
+    assert!(
+        warnings.contains("`body` never matches inside a shadow root"),
+        "{warnings}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    maps_lifecycle_hooks_to_element_callbacks,
+    r#"
+import { onMount, onCleanup } from "@fluxel/core";
+
+export default function Clock() {
+  onMount(() => console.log("mounted"));
+  onCleanup(() => console.log("removed"));
+  return <p>Tick</p>;
+}
+"#,
+    r#"
+import { onMount, onCleanup, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Clock() {
+  onMount(() => console.log("mounted"));
+  onCleanup(() => console.log("removed"));
+  const _p = document.createElement("p");
+  _p.textContent = "Tick";
+  return _p;
+}
+class ClockElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Clock(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-clock", ClockElement);
+export default ClockElement;
+"#
+);
+
+#[test]
+fn reports_nested_lifecycle_hooks() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+import { onMount } from "@fluxel/core";
+export default function Clock() {
+  const start = () => onMount(() => {});
+  return <button onClick={start}>Start</button>;
+}
+"#,
+    );
+
+    assert!(
+        errors.contains("`onMount` must be called at the top level of the component"),
+        "{errors}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            defer_render: false,
+            ..Default::default()
+        }
+    ),
+    renders_in_constructor_without_defer_render,
+    r#"
+export default function Hello() {
+  return <p>Hi</p>;
+}
+"#,
+    r#"
+function Hello() {
+  const _p = document.createElement("p");
+  _p.textContent = "Hi";
+  return _p;
+}
+class HelloElement extends HTMLElement {
+  #props = {};
+  constructor() {
+    super();
+    const _n = Hello(this.#props);
+    this.attachShadow({ mode: "open" }).appendChild(_n);
+  }
+}
+customElements.define("fluxel-hello", HelloElement);
+export default HelloElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            defer_render: false,
+            ..Default::default()
+        }
+    ),
+    renders_again_after_disconnect,
+    r#"
+import { onMount } from "@fluxel/core";
+
+interface ClockProps {
+  /** @reflect */
+  paused?: boolean;
+}
+
+export default function Clock({ paused }: ClockProps) {
+  onMount(() => console.log("mounted"));
+  return <p hidden={paused}>Tick</p>;
+}
+"#,
+    r#"
+import { onMount, effect, signal, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+interface ClockProps {
+  paused?: boolean;
+}
+function Clock(__props) {
+  onMount(() =>console.log("mounted"));
+  const _p = document.createElement("p");
+  effect(() =>_p.toggleAttribute("hidden", !!__props.paused.value));
+  _p.textContent = "Tick";
+  return _p;
+}
+class ClockElement extends HTMLElement {
+  static observedAttributes = ["paused"];
+  #props = { paused: signal() };
+  #reflecting = false;
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["paused"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+    this.#rendered = true;
+    const _n = render(this.#lifecycle, () =>Clock(this.#props));
+    this.#root.appendChild(_n);
+    this.#lifecycle.effects.push(effect(() => {
+      const value = this.#props.paused.value;
+      if (value !== undefined) {
+        this.#reflecting = true;
+        this.toggleAttribute("paused", !!value);
+        this.#reflecting = false;
+      }
+    }));
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () =>Clock(this.#props));
+      this.#root.appendChild(_n);
+      this.#lifecycle.effects.push(effect(() => {
+        const value = this.#props.paused.value;
+        if (value !== undefined) {
+          this.#reflecting = true;
+          this.toggleAttribute("paused", !!value);
+          this.#reflecting = false;
+        }
+      }));
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () =>{
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    if (this.#reflecting) return;
+    switch (name) {
+      case "paused":
+        this.#props.paused.value = value !== null;
+        break;
+    }
+  }
+  get paused() {
+    return this.#props.paused.value;
+  }
+  set paused(value) {
+    this.#props.paused.value = value;
+  }
+}
+customElements.define("fluxel-clock", ClockElement);
+export default ClockElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    associates_elements_with_forms,
+    r#"
+import { signal, useFormValue } from "@fluxel/core";
+
+export default function Rating() {
+  const stars = signal(0);
+  useFormValue(stars);
+  return <button onClick={() => stars.value++}>Rate</button>;
+}
+"#,
+    r#"
+import { signal, useFormValue, createLifecycle, render, connect, disconnect, resetForm, restoreForm } from "@fluxel/core";
+function Rating() {
+  const stars = signal(0);
+  useFormValue(stars);
+  const _button = document.createElement("button");
+  _button.addEventListener("click", () => stars.value++);
+  _button.textContent = "Rate";
+  return _button;
+}
+class RatingElement extends HTMLElement {
+  static formAssociated = true;
+  #props = {};
+  #lifecycle = createLifecycle(this.attachInternals());
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Rating(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  formResetCallback() {
+    resetForm(this.#lifecycle);
+  }
+  formStateRestoreCallback(state, mode) {
+    restoreForm(this.#lifecycle, state, mode);
+  }
+}
+customElements.define("fluxel-rating", RatingElement);
+export default RatingElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    associates_tagged_elements_with_forms,
+    r#"
+/** @formAssociated */
+export default function Toggle() {
+  return <button>Toggle</button>;
+}
+"#,
+    r#"
+import { createLifecycle, render, connect, disconnect, resetForm, restoreForm } from "@fluxel/core";
+function Toggle() {
+  const _button = document.createElement("button");
+  _button.textContent = "Toggle";
+  return _button;
+}
+class ToggleElement extends HTMLElement {
+  static formAssociated = true;
+  #props = {};
+  #lifecycle = createLifecycle(this.attachInternals());
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Toggle(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  formResetCallback() {
+    resetForm(this.#lifecycle);
+  }
+  formStateRestoreCallback(state, mode) {
+    restoreForm(this.#lifecycle, state, mode);
+  }
+}
+customElements.define("fluxel-toggle", ToggleElement);
+export default ToggleElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    applies_exported_shadow_options,
+    r#"
+export const config = { shadow: "closed", delegatesFocus: true, slotAssignment: "manual" };
+
+export default function Field() {
+  return <input />;
+}
+"#,
+    r#"
+function Field() {
+  const _input = document.createElement("input");
+  return _input;
+}
+class FieldElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "closed", delegatesFocus: true, slotAssignment: "manual" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Field(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-field", FieldElement);
+export default FieldElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    keeps_config_read_by_the_module,
+    r#"
+export const config = { shadow: "closed" };
+console.log(config.shadow);
+
+export default function Field() {
+  return <input />;
+}
+"#,
+    r#"
+export const config = { shadow: "closed" };
+console.log(config.shadow);
+function Field() {
+  const _input = document.createElement("input");
+  return _input;
+}
+class FieldElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "closed" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Field(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-field", FieldElement);
+export default FieldElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    keeps_unrelated_config_export,
+    r#"
+export const config = { apiUrl: "/api" };
+
+export default function Field() {
+  return <input />;
+}
+"#,
+    r#"
+export const config = { apiUrl: "/api" };
+function Field() {
+  const _input = document.createElement("input");
+  return _input;
+}
+class FieldElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Field(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-field", FieldElement);
+export default FieldElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    renders_into_light_dom,
+    r#"
+/** @shadow false */
+export default function Banner() {
+  return <p>Sale</p>;
+}
+"#,
+    r#"
+function Banner() {
+  const _p = document.createElement("p");
+  _p.textContent = "Sale";
+  return _p;
+}
+class BannerElement extends HTMLElement {
+  #props = {};
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Banner(this.#props);
+      this.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-banner", BannerElement);
+export default BannerElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    projects_children_into_slots,
+    r#"
+import { Slot } from "@fluxel/core";
+export default function Card({ title, children, slots }: { title: string }) {
+  return (
+    <article>
+      <h2>{slots.header}</h2>
+      {children}
+      <Slot name="footer">{title}</Slot>
+    </article>
+  );
+}
+"#,
+    r#"
+import { Slot, insert, signal, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Card(__props) {
+  const _article = document.createElement("article");
+  const _h2 = document.createElement("h2");
+  const _slot = document.createElement("slot");
+  _slot.setAttribute("name", "header");
+  _h2.append(_slot);
+  const _slot1 = document.createElement("slot");
+  const _slot2 = document.createElement("slot");
+  _slot2.setAttribute("name", "footer");
+  _slot2.append(insert(() => __props.title.value));
+  _article.append(_h2, _slot1, _slot2);
+  return _article;
+}
+class CardElement extends HTMLElement {
+  static observedAttributes = [
+    "title"
+  ];
+  #props = {
+    title: signal()
+  };
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({
+    mode: "open"
+  });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["title"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, ()=>Card(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch(name){
+      case "title":
+        this.#props.title.value = value;
+        break;
+    }
+  }
+  get title() {
+    return this.#props.title.value;
+  }
+  set title(value) {
+    this.#props.title.value = value;
+  }
+}
+customElements.define("fluxel-card", CardElement);
+export default CardElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    projects_children_in_conditions,
+    r#"
+const open = true;
+
+export default function Panel({ children }) {
+  return <section>{open ? children : <p>Closed</p>}</section>;
+}
+"#,
+    r#"
+import { toChildren } from "@fluxel/core";
+const open = true;
+function Panel(__props) {
+  const _section = document.createElement("section");
+  _section.append(...toChildren(open ? (() => {
+    const _slot = document.createElement("slot");
+    return _slot;
+  })() : (() => {
+    const _p = document.createElement("p");
+    _p.textContent = "Closed";
+    return _p;
+  })()));
+  return _section;
+}
+class PanelElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Panel(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-panel", PanelElement);
+export default PanelElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    projects_slots_of_props,
+    r#"
+export default function Panel(props) {
+  return <section>{props.slots["aside"]}{props.children}</section>;
+}
+"#,
+    r#"
+function Panel(props) {
+  const _section = document.createElement("section");
+  const _slot = document.createElement("slot");
+  _slot.setAttribute("name", "aside");
+  const _slot1 = document.createElement("slot");
+  _section.append(_slot, _slot1);
+  return _section;
+}
+class PanelElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({
+    mode: "open"
+  });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Panel(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-panel", PanelElement);
+export default PanelElement;
+"#
+);
+
+#[test]
+fn reports_slots_in_light_dom() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+/** @shadow false */
+export default function Banner({ children }) {
+  return <p>{children}</p>;
+}
+"#,
+    );
+
+    assert!(errors.contains("slots need a shadow root"), "{errors}");
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    dispatches_declared_events,
+    r#"
+import { defineEvents } from "@fluxel/core";
+interface StepperEvents {
+  change: number;
+  reset: void;
+}
+export default function Stepper() {
+  const emit = defineEvents<StepperEvents>();
+  return (
+    <div>
+      <button onClick={() => emit("change", 1)}>+</button>
+      <button onClick={() => emit("reset")}>0</button>
+    </div>
+  );
+}
+"#,
+    r#"
+import { defineEvents } from "@fluxel/core";
+interface StepperEvents {
+  change: number;
+  reset: void;
+}
+function Stepper(__props, __host) {
+  const _div = document.createElement("div");
+  const _button = document.createElement("button");
+  _button.addEventListener("click", ()=>__host.dispatchEvent(new CustomEvent("change", {
+      detail: 1,
+      bubbles: true,
+      composed: true
+    })));
+  _button.textContent = "+";
+  const _button1 = document.createElement("button");
+  _button1.addEventListener("click", ()=>__host.dispatchEvent(new CustomEvent("reset", {
+      bubbles: true,
+      composed: true
+    })));
+  _button1.textContent = "0";
+  _div.append(_button, _button1);
+  return _div;
+}
+class StepperElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({
+    mode: "open"
+  });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Stepper(this.#props, this);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-stepper", StepperElement);
+export default StepperElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    dispatches_emitted_events,
+    r#"
+import { emit } from "@fluxel/core";
+export default function Search({ query }: { query: string }) {
+  return <input onInput={(e) => emit("search", e.target.value)} value={query} />;
+}
+"#,
+    r#"
+import { emit, setAttr, effect, signal, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Search(__props, __host) {
+  const _input = document.createElement("input");
+  _input.addEventListener("input", (e)=>__host.dispatchEvent(new CustomEvent("search", {
+      detail: e.target.value,
+      bubbles: true,
+      composed: true
+    })));
+  effect(()=>setAttr(_input, "value", __props.query.value));
+  return _input;
+}
+class SearchElement extends HTMLElement {
+  static observedAttributes = [
+    "query"
+  ];
+  #props = {
+    query: signal()
+  };
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({
+    mode: "open"
+  });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["query"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, ()=>Search(this.#props, this));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch(name){
+      case "query":
+        this.#props.query.value = value;
+        break;
+    }
+  }
+  get query() {
+    return this.#props.query.value;
+  }
+  set query(value) {
+    this.#props.query.value = value;
+  }
+}
+customElements.define("fluxel-search", SearchElement);
+export default SearchElement;
+"#
+);
+
+#[test]
+fn reports_undeclared_events() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+import { defineEvents } from "@fluxel/core";
+export default function Toggle() {
+  const emit = defineEvents<{ toggle: boolean }>();
+  const name = "close";
+  return <button onClick={() => { emit("open"); emit(name); }} />;
+}
+"#,
+    );
+
+    assert!(
+        errors.contains("`open` is not one of the events declared by `defineEvents`"),
+        "{errors}"
+    );
+    assert!(
+        errors.contains("the event name passed to `emit` must be a string literal"),
+        "{errors}"
+    );
+}
+
+#[test]
+fn reports_invalid_component_options() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+export const config = { shadow: "shut", delegatesFocus: "yes" };
+export default function Field() {
+  return <input />;
+}
+"#,
+    );
+
+    assert!(
+        errors.contains("`shadow` must be \"open\", \"closed\" or false"),
+        "{errors}"
+    );
+    assert!(
+        errors.contains("`delegatesFocus` must be a boolean"),
+        "{errors}"
+    );
+}
+
+#[test]
+fn leaves_unrelated_config_alone() {
+    for config in ["loadConfig()", r#"{ apiUrl: "/api", shadow: "open" }"#] {
+        let src = format!(
+            "export const config = {config};\nexport default function Field() {{ return <input />; }}"
+        );
+        let diagnostics = transform_warnings(Config::default(), &src);
+        assert!(diagnostics.is_empty(), "{diagnostics}");
+    }
+}
+
+#[test]
+fn reports_reads_of_slotted_children() {
+    for body in ["!!children", "children ? <p>Full</p> : <p>Empty</p>"] {
+        let src = format!(
+            "export default function Panel({{ children }}) {{ return <section>{{{body}}}</section>; }}"
+        );
+        let errors = transform_errors(Config::default(), &src);
+        assert!(
+            errors.contains("slotted children can only be rendered"),
+            "{errors}"
+        );
+    }
+}
+
+#[test]
+fn reports_for_without_row_function() {
+    let errors = transform_errors(
+        Config::default(),
+        r#"
+import { For } from "@fluxel/core";
+export default function List({ items }) {
+  return <ul><For each={items}><li /></For></ul>;
+}
+"#,
+    );
+
+    assert!(
+        errors.contains("`<For>` takes a single function child"),
+        "{errors}"
+    );
+}
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    compiles_nested_jsx_expressions,
+    r#"
+export default function TodoList({ items }) {
+  const title = <h2 id="title">Todos</h2>;
+  return (
+    <>
+      {title}
+      <ul hidden={items.length === 0}>{items.map((item) => <li>{item}</li>)}</ul>
+      <svg viewBox="0 0 10 10"><circle class="dot" r="5" /></svg>
+    </>
+  );
+}
+"#,
+    r#"
+import { toChildren, effect, createFor, signal, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function TodoList(__props) {
+  const _h2 = document.createElement("h2");
+  _h2.setAttribute("id", "title");
+  _h2.textContent = "Todos";
+  const title = _h2;
+  const _frag = document.createDocumentFragment();
+  const _ul = document.createElement("ul");
+  effect(() => _ul.toggleAttribute("hidden", !!(__props.items.value.length === 0)));
+  _ul.append(createFor(() => __props.items.value, (item) => {
+    const _li = document.createElement("li");
+    _li.append(...toChildren(item));
+    return _li;
+  }));
+  const _svg = document.createElementNS("http://www.w3.org/2000/svg", "svg");
+  _svg.setAttribute("viewBox", "0 0 10 10");
+  const _circle = document.createElementNS("http://www.w3.org/2000/svg", "circle");
+  _circle.setAttribute("class", "dot");
+  _circle.setAttribute("r", "5");
+  _svg.append(_circle);
+  _frag.append(...toChildren(title), _ul, _svg);
+  return _frag;
+}
+class TodoListElement extends HTMLElement {
+  static observedAttributes = ["items"];
+  #props = { items: signal() };
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["items"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => TodoList(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "items":
+        this.#props.items.value = value;
+        break;
+    }
+  }
+  get items() {
+    return this.#props.items.value;
+  }
+  set items(value) {
+    this.#props.items.value = value;
+  }
+}
+customElements.define("fluxel-todo-list", TodoListElement);
+export default TodoListElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    skips_children_rendering_nothing,
+    r#"
+export default function Notice({ items }) {
+  const show = items.length > 0;
+  const label = "Notice";
+  return (
+    <p>
+      {null}
+      {undefined}
+      {false}
+      {show && <b>{label}</b>}
+      {`${label}!`}
+    </p>
+  );
+}
+"#,
+    r#"
+import { toChildren, signal } from "@fluxel/core";
+function Notice(__props) {
+  const show = __props.items.value.length > 0;
+  const label = "Notice";
+  const _p = document.createElement("p");
+  _p.append(...toChildren(show && (() => {
+    const _b = document.createElement("b");
+    _b.append(...toChildren(label));
+    return _b;
+  })()), `${label}!`);
+  return _p;
+}
+class NoticeElement extends HTMLElement {
+  static observedAttributes = [
+    "items"
+  ];
+  #props = {
+    items: signal()
+  };
+  #root = this.attachShadow({
+    mode: "open"
+  });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["items"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Notice(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch(name){
+      case "items":
+        this.#props.items.value = value;
+        break;
+    }
+  }
+  get items() {
+    return this.#props.items.value;
+  }
+  set items(value) {
+    this.#props.items.value = value;
+  }
+}
+customElements.define("fluxel-notice", NoticeElement);
+export default NoticeElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    inserts_reactive_children_rendering_nodes,
+    r#"
+import { signal } from "@fluxel/core";
+
+export default function Sign() {
+  const n = signal(0);
+  const items = signal<string[] | null>(null);
+  return (
+    <p>
+      {n} {-n.value} {n.value > 0 ? <i>pos</i> : "zero"} {items}
+    </p>
+  );
+}
+"#,
+    r#"
+import { signal, effect, insert, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Sign() {
+  const n = signal(0);
+  const items = signal<string[] | null>(null);
+  const _p = document.createElement("p");
+  const _txt = document.createTextNode("");
+  effect(() =>_txt.data = String(n.value));
+  const _txt1 = document.createTextNode("");
+  effect(() =>_txt1.data = String(-n.value));
+  _p.append(_txt, " ", _txt1, " ", insert(() =>n.value > 0 ? (() => {
+      const _i = document.createElement("i");
+      _i.textContent = "pos";
+      return _i;
+    })() : "zero"), " ", insert(() =>items.value));
+  return _p;
+}
+class SignElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () =>Sign(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-sign", SignElement);
+export default SignElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel(t),
+    renders_lifted_components_as_their_elements,
+    r#"
+import { signal } from "@fluxel/core";
+
+/** @customElement */
+function Badge({ count, label }: { count: number; label: string }) {
+  return <span title={label}>{count}</span>;
+}
+
+export default function Inbox() {
+  const unread = signal(3);
+  return (
+    <Badge count={unread} label="Unread" data-kind="mail" onReset={() => (unread.value = 0)}>
+      New
+    </Badge>
+  );
+}
+"#,
+    r#"
+import { signal, setAttr, effect, insert, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Badge(__props) {
+  const _span = document.createElement("span");
+  effect(() => setAttr(_span, "title", __props.label.value));
+  _span.append(insert(() => __props.count.value));
+  return _span;
+}
+class BadgeElement extends HTMLElement {
+  static observedAttributes = ["count", "label"];
+  #props = { count: signal(), label: signal() };
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["count", "label"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Badge(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "count":
+        this.#props.count.value = value === null ? null : Number(value);
+        break;
+      case "label":
+        this.#props.label.value = value;
+        break;
+    }
+  }
+  get count() {
+    return this.#props.count.value;
+  }
+  set count(value) {
+    this.#props.count.value = value;
+  }
+  get label() {
+    return this.#props.label.value;
+  }
+  set label(value) {
+    this.#props.label.value = value;
+  }
+}
+customElements.define("fluxel-badge", BadgeElement);
+function Inbox() {
+  const unread = signal(3);
+  const _fluxel_badge = document.createElement("fluxel-badge");
+  effect(() => _fluxel_badge.count = unread.value);
+  _fluxel_badge.label = "Unread";
+  _fluxel_badge.setAttribute("data-kind", "mail");
+  _fluxel_badge.addEventListener("reset", () => unread.value = 0);
+  _fluxel_badge.textContent = "New";
+  return _fluxel_badge;
+}
+class InboxElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Inbox(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-inbox", InboxElement);
+export default InboxElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            codegen: Codegen::Template,
+            ..Default::default()
+        }
+    ),
+    clones_hoisted_templates,
+    r#"
+import { signal } from "@fluxel/core";
+
+export default function Counter() {
+  const count = signal(0);
+  return (
+    <div class="counter">
+      <h1>Counter &amp; "friends"</h1>
+      <p>Count: {count} clicks</p>
+      <input type="number" disabled />
+      <button onclick={() => count.value++}>+</button>
+    </div>
+  );
+}
+"#,
+    r#"
+import { signal, effect, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+const _tmpl = document.createElement("template");
+_tmpl.innerHTML = '<div class="counter"><h1>Counter &amp; "friends"</h1><p>Count: <!> clicks</p><input type="number" disabled><button>+</button></div>';
+function Counter() {
+  const count = signal(0);
+  const _div = _tmpl.content.firstChild.cloneNode(true);
+  const _p = _div.firstChild.nextSibling;
+  const _marker = _p.firstChild.nextSibling;
+  const _button = _p.nextSibling.nextSibling;
+  const _txt = document.createTextNode("");
+  effect(() => _txt.data = String(count.value));
+  _marker.replaceWith(_txt);
+  _button.addEventListener("click", () => count.value++);
+  return _div;
+}
+class CounterElement extends HTMLElement {
+  #props = {};
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Counter(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+}
+customElements.define("fluxel-counter", CounterElement);
+export default CounterElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            codegen: Codegen::Template,
+            ..Default::default()
+        }
+    ),
+    leaves_no_hole_for_empty_children,
+    r#"
+export default function Empty() {
+  return (
+    <p>
+      Nothing {null} {false} here
+    </p>
+  );
+}
+"#,
+    r#"
+const _tmpl = document.createElement("template");
+_tmpl.innerHTML = "<p>Nothing   here</p>";
+function Empty() {
+  const _p = _tmpl.content.firstChild.cloneNode(true);
+  return _p;
+}
+class EmptyElement extends HTMLElement {
+  #props = {};
+  #root = this.attachShadow({
+    mode: "open"
+  });
+  #rendered = false;
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = Empty(this.#props);
+      this.#root.appendChild(_n);
+    }
+  }
+}
+customElements.define("fluxel-empty", EmptyElement);
+export default EmptyElement;
+"#
+);
+
+test_inline!(
+    tsx(),
+    |t| fluxel_with(
+        t,
+        Config {
+            codegen: Codegen::Template,
+            ..Default::default()
+        }
+    ),
+    builds_tables_without_templates,
+    r#"
+export default function Row({ n }: { n: number }) {
+  return (
+    <table>
+      <tr>
+        <td>{n}</td>
+      </tr>
+    </table>
+  );
+}
+"#,
+    r#"
+import { insert, signal, createLifecycle, render, connect, disconnect } from "@fluxel/core";
+function Row(__props) {
+  const _table = document.createElement("table");
+  const _tr = document.createElement("tr");
+  const _td = document.createElement("td");
+  _td.append(insert(() => __props.n.value));
+  _tr.append(_td);
+  _table.append(_tr);
+  return _table;
+}
+class RowElement extends HTMLElement {
+  static observedAttributes = ["n"];
+  #props = { n: signal() };
+  #lifecycle = createLifecycle();
+  #root = this.attachShadow({ mode: "open" });
+  #rendered = false;
+  constructor() {
+    super();
+    for (const name of ["n"]) {
+      if (Object.hasOwn(this, name)) {
+        const value = this[name];
+        delete this[name];
+        this[name] = value;
+      }
+    }
+  }
+  connectedCallback() {
+    if (!this.#rendered) {
+      this.#rendered = true;
+      const _n = render(this.#lifecycle, () => Row(this.#props));
+      this.#root.appendChild(_n);
+    }
+    connect(this.#lifecycle);
+  }
+  disconnectedCallback() {
+    disconnect(this.#lifecycle, this, () => {
+      this.#rendered = false;
+      this.#root.replaceChildren();
+    });
+  }
+  attributeChangedCallback(name, _old, value) {
+    switch (name) {
+      case "n":
+        this.#props.n.value = value === null ? null : Number(value);
+        break;
+    }
+  }
+  get n() {
+    return this.#props.n.value;
+  }
+  set n(value) {
+    this.#props.n.value = value;
+  }
+}
+customElements.define("fluxel-row", RowElement);
+export default RowElement;
+"#
 );
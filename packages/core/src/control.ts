@@ -0,0 +1,233 @@
+// Control-flow components. The compiler turns `<Show>` and `<For>` into
+// direct calls of `createShow` / `createFor`; the components themselves only
+// serve code that goes through the JSX runtime. `toChildren` and `insert`
+// render the other child expressions of compiled JSX. Each branch and row
+// owns the effects it creates, which are disposed when it is swapped out.
+import {Signal, effect, isSignal, onDispose, owned, untracked} from "./signals";
+
+/** One rendered item of a `<For>` list. */
+interface Row<T> {
+  item: T;
+  nodes: Node[];
+  dispose: () => void;
+}
+
+/**
+ * Top-level nodes of a rendered subtree, so they can be moved or removed
+ * after a fragment has been emptied into the document.
+ * @param node rendered node
+ * @returns its nodes
+ * @internal
+ */
+function nodesOf(node: Node | null | undefined): Node[] {
+  if (node == null) return [];
+  return node instanceof DocumentFragment ? Array.from(node.childNodes) : [node];
+}
+
+/**
+ * @param nodes nodes to detach
+ * @internal
+ */
+function remove(nodes: Node[]): void {
+  nodes.forEach((node) => node.parentNode?.removeChild(node));
+}
+
+/**
+ * Renders `fn` untracked, in a scope owning the effects it creates.
+ * @param fn renders the content
+ * @returns its nodes and the disposer of its effects
+ * @internal
+ */
+function scoped(fn: () => Node | null | undefined): [Node[], () => void] {
+  const disposers: Array<() => void> = [];
+  const nodes = nodesOf(owned(disposers, () => untracked(fn)));
+  return [nodes, () => disposers.splice(0).forEach((dispose) => dispose())];
+}
+
+/**
+ * Fragment holding the comment that marks where a control-flow block sits.
+ * Content is inserted right before the anchor, which stays in place once the
+ * fragment is appended to its parent.
+ * @param name comment text, for debugging
+ * @returns the fragment and its anchor
+ * @internal
+ */
+function anchored(name: string): [DocumentFragment, Comment] {
+  const anchor = document.createComment(name);
+  const root = document.createDocumentFragment();
+  root.append(anchor);
+  return [root, anchor];
+}
+
+/**
+ * Arguments of `append` for a child expression of compiled JSX: arrays are
+ * flattened, and `null`, `undefined` and booleans render nothing.
+ * @param value child expression, e.g. `{show && <b />}`
+ * @returns nodes and strings to append
+ */
+export function toChildren(value: unknown): Array<Node | string> {
+  if (Array.isArray(value)) return value.flatMap(toChildren);
+  if (value == null || typeof value === "boolean") return [];
+  return [value instanceof Node ? value : String(value)];
+}
+
+/**
+ * Renders the child expression `value` before an anchor, again whenever the
+ * signals it reads change. Text is updated in place; nodes, arrays and the
+ * values rendering nothing replace the previous content.
+ * @param value child expression, e.g. `() => n.value > 0 ? <i>pos</i> : "zero"`
+ * @returns a fragment to insert into the parent
+ */
+export function insert(value: () => unknown): Node {
+  const [root, anchor] = anchored("insert");
+  let nodes: Node[] = [];
+  const disposers: Array<() => void> = [];
+  const clear = () => disposers.splice(0).forEach((dispose) => dispose());
+  onDispose(clear);
+
+  effect(() => {
+    clear();
+    const next = toChildren(owned(disposers, value));
+    // a single text replacing a single text
+    const [text] = nodes;
+    const [data] = next;
+    if (next.length === 1 && typeof data === "string" && text instanceof Text && !nodes[1]) {
+      text.data = data;
+      return;
+    }
+    remove(nodes);
+    nodes = next.flatMap((child) =>
+      typeof child === "string" ? [document.createTextNode(child)] : nodesOf(child)
+    );
+    nodes.forEach((node) => anchor.parentNode!.insertBefore(node, anchor));
+  });
+  return root;
+}
+
+/**
+ * Renders `children` while `when` is truthy and `fallback` otherwise,
+ * swapping the nodes only when the condition flips.
+ * @param when condition, re-evaluated when the signals it reads change
+ * @param children renders the content
+ * @param fallback renders the content shown while `when` is falsy
+ * @returns a fragment to insert into the parent
+ */
+export function createShow(when: () => unknown, children: () => Node, fallback?: () => Node): Node {
+  const [root, anchor] = anchored("show");
+  let shown: boolean | undefined;
+  let nodes: Node[] = [];
+  let dispose = () => {};
+  onDispose(() => dispose());
+
+  effect(() => {
+    const next = !!when();
+    if (next === shown) return;
+    shown = next;
+    remove(nodes);
+    dispose();
+    [nodes, dispose] = scoped(() => (next ? children() : fallback?.()));
+    nodes.forEach((node) => anchor.parentNode!.insertBefore(node, anchor));
+  });
+  return root;
+}
+
+/**
+ * Renders one row per item of `each`. Rows are matched to items by `key`
+ * (the item itself by default): new items are rendered, rows of removed items
+ * are dropped and the remaining rows are moved only when out of place. A row
+ * whose key now belongs to another object, e.g. an updated copy, is rendered
+ * again.
+ * @param each list, re-evaluated when the signals it reads change
+ * @param render renders the row of an item
+ * @param key identity of an item across updates
+ * @returns a fragment to insert into the parent
+ */
+export function createFor<T>(
+  each: () => readonly T[] | null | undefined,
+  render: (item: T, index: number) => Node,
+  key: (item: T, index: number) => unknown = (item) => item,
+): Node {
+  const [root, anchor] = anchored("for");
+  let rows = new Map<unknown, Row<T>>();
+  const drop = (row: Row<T>) => {
+    remove(row.nodes);
+    row.dispose();
+  };
+  onDispose(() => rows.forEach((row) => row.dispose()));
+
+  effect(() => {
+    const items = each() ?? [];
+    const next = new Map<unknown, Row<T>>();
+    const order: Row<T>[] = [];
+
+    items.forEach((item, index) => {
+      const id = key(item, index);
+      let row = rows.get(id);
+      if (row) rows.delete(id);
+      if (row && row.item !== item) {
+        drop(row);
+        row = undefined;
+      }
+      if (!row) {
+        const [nodes, dispose] = scoped(() => render(item, index));
+        row = {item, nodes, dispose};
+      }
+      // a duplicate key gets a row of its own
+      next.set(next.has(id) ? Symbol() : id, row);
+      order.push(row);
+    });
+
+    rows.forEach(drop);
+    rows = next;
+
+    // walk backwards so each row only has to land before the one after it
+    const parent = anchor.parentNode!;
+    let before: Node = anchor;
+    for (let i = order.length - 1; i >= 0; i--) {
+      const {nodes} = order[i];
+      if (!nodes.length) continue;
+      if (nodes[nodes.length - 1].nextSibling !== before) {
+        nodes.forEach((node) => parent.insertBefore(node, before));
+      }
+      before = nodes[0];
+    }
+  });
+  return root;
+}
+
+/**
+ * Reads a prop that may be passed as a signal.
+ * @param value prop value
+ * @returns current value
+ * @internal
+ */
+function read<T>(value: T | Signal<T>): T {
+  return isSignal(value) ? value.value : value;
+}
+
+/**
+ * `<Show when={open} fallback={<p>Closed</p>}>...</Show>`
+ * @param props `when`, `fallback` and `children`
+ * @returns the rendered nodes
+ */
+export function Show(props: {when: unknown; fallback?: Node; children?: Node | Node[]}): Node {
+  const children = () => {
+    const root = document.createDocumentFragment();
+    root.append(...[props.children ?? []].flat());
+    return root;
+  };
+  return createShow(
+    () => read(props.when),
+    children,
+    props.fallback === undefined ? undefined : () => props.fallback!,
+  );
+}
+
+/**
+ * `<For each={items}>{(item) => <li>{item}</li>}</For>`
+ * @param props `each` and the row renderer as `children`
+ * @returns the rendered nodes
+ */
+export function For<T>(props: {each: readonly T[] | Signal<readonly T[]>; children: (item: T, index: number) => Node}): Node {
+  return createFor(() => read(props.each), props.children);
+}
@@ -0,0 +1,86 @@
+/** React-style names whose DOM event is not simply the lowercased name. */
+const renamed: Record<string, string> = {doubleclick: "dblclick"};
+
+/**
+ * DOM event listened to by an `onX` / `on:x` prop.
+ * @param key prop name, e.g. `onDoubleClick` or `on:item-selected`
+ * @returns event name, e.g. `dblclick` or `item-selected`
+ */
+export function eventName(key: string): string {
+  if (key.startsWith("on:")) return key.slice(3);
+  const name = key.slice(2).toLowerCase();
+  return renamed[name] ?? name;
+}
+
+/** Roots that already dispatch a given event. */
+const registered = new WeakMap<Node, Set<string>>();
+
+/**
+ * Dispatch `events` from a single listener on `root` to the `$$<event>`
+ * handler stored on the nodes in the event path, innermost first.
+ * Used by compiled elements built with `delegateEvents: true`.
+ * @param root shadow root (or any node) owning the handlers
+ * @param events event names, e.g. `["click", "input"]`
+ */
+export function delegateEvents(root: Node, events: string[]): void {
+  let names = registered.get(root);
+  if (!names) registered.set(root, (names = new Set()));
+  for (const name of events) {
+    if (names.has(name)) continue;
+    names.add(name);
+    root.addEventListener(name, dispatch);
+  }
+}
+
+/** Root whose listener last dispatched an event, innermost first. */
+const dispatched = new WeakMap<Event, EventTarget>();
+
+/**
+ * Walks the composed path up to the root the listener sits on. The path of
+ * an event from a nested element also crosses the shadow roots inside this
+ * one; their listeners ran first and handled the nodes up to them.
+ * @param event event being dispatched
+ * @internal
+ */
+function dispatch(this: Node, event: Event): void {
+  const key = `$$${event.type}`;
+  const path = event.composedPath();
+  const inner = dispatched.get(event);
+  dispatched.set(event, this);
+  for (let i = inner ? path.indexOf(inner) + 1 : 0; i < path.length; i++) {
+    const node = path[i];
+    if (node === this) break;
+    const handler = (node as any)[key];
+    if (typeof handler !== "function") continue;
+    // handlers see the node they were set on, as with addEventListener
+    Object.defineProperty(event, "currentTarget", {configurable: true, value: node});
+    handler.call(node, event);
+    if (event.cancelBubble) break;
+  }
+}
+
+/** Dispatches the event `name` of an element, with `detail` unless its type is `void`. */
+export type Emit<E> = <K extends keyof E & string>(
+  name: K,
+  ...detail: E[K] extends void | undefined ? [] : [detail: E[K]]
+) => void;
+
+/**
+ * Declares the custom events a component dispatches, keyed by name with the
+ * type of their `detail`. The compiler removes the call and dispatches the
+ * events on the element; uncompiled code has no element to dispatch on.
+ * @returns a typed `emit`
+ */
+export function defineEvents<E extends Record<string, unknown>>(): Emit<E> {
+  return emit as Emit<E>;
+}
+
+/**
+ * Dispatches the bubbling, composed event `name` from the element of the
+ * component. Only has an effect in a compiled component.
+ * @param name event name
+ * @param detail `event.detail`
+ */
+export function emit(name: string, detail?: unknown): void {
+  throw new Error(`emit("${name}") must be compiled by @fluxel/swc-plugin`);
+}
@@ -0,0 +1,84 @@
+// Form-associated elements. The compiler marks components calling these hooks
+// `static formAssociated = true`, creates their lifecycle with the element's
+// `ElementInternals` and forwards `formResetCallback` /
+// `formStateRestoreCallback` to `resetForm` / `restoreForm`. The hooks only
+// have an effect at the top level of a compiled component.
+import {Lifecycle, currentLifecycle} from "./lifecycle";
+import {Signal, effect} from "./signals";
+
+/** A value `ElementInternals.setFormValue` accepts. */
+type FormValue = File | string | FormData | null;
+
+/**
+ * Converts a signal value into a submitted form value: `null` and `false`
+ * leave the element out of the submission, `true` submits `"on"` like a
+ * checkbox.
+ * @param value signal value
+ * @returns form value
+ * @internal
+ */
+function toFormValue(value: unknown): FormValue {
+  if (value == null || value === false) return null;
+  if (value === true) return "on";
+  if (value instanceof File || value instanceof FormData) return value;
+  return String(value);
+}
+
+/**
+ * Submits `value` with the element's form. The signal is reset to its current
+ * value when the form is reset, and set to the saved state when the browser
+ * restores the form.
+ * @param value signal holding the value of the element
+ * @returns the element's internals, e.g. for `setValidity`
+ */
+export function useFormValue<T>(value: Signal<T>): ElementInternals | undefined {
+  const lifecycle = currentLifecycle();
+  const internals = lifecycle?.internals;
+  if (!lifecycle || !internals) return undefined;
+
+  const initial = value.value;
+  effect(() => internals.setFormValue(toFormValue(value.value)));
+  lifecycle.formResets.push(() => (value.value = initial));
+  lifecycle.formRestores.push((state) => (value.value = state as T));
+  return internals;
+}
+
+/** @returns the element's internals, to set its validity or form value */
+export function useInternals(): ElementInternals | undefined {
+  return currentLifecycle()?.internals;
+}
+
+/**
+ * Runs `fn` when the form owning the element is reset.
+ * @param fn reset hook
+ */
+export function onFormReset(fn: () => void): void {
+  currentLifecycle()?.formResets.push(fn);
+}
+
+/**
+ * Runs `fn` when the browser restores the state of the element, e.g. on
+ * back navigation or autofill.
+ * @param fn receives the saved state and `"restore"` or `"autocomplete"`
+ */
+export function onFormStateRestore(fn: (state: unknown, mode: string) => void): void {
+  currentLifecycle()?.formRestores.push(fn);
+}
+
+/**
+ * Runs the reset hooks. Called by `formResetCallback`.
+ * @param lifecycle lifecycle of the element
+ */
+export function resetForm(lifecycle: Lifecycle): void {
+  lifecycle.formResets.forEach((reset) => reset());
+}
+
+/**
+ * Runs the restore hooks. Called by `formStateRestoreCallback`.
+ * @param lifecycle lifecycle of the element
+ * @param state saved state
+ * @param mode `"restore"` or `"autocomplete"`
+ */
+export function restoreForm(lifecycle: Lifecycle, state: unknown, mode: string): void {
+  lifecycle.formRestores.forEach((restore) => restore(state, mode));
+}
@@ -1,7 +1,12 @@
 import {Signal, signal} from "./signals";
 
 export * from "./signals";
-export {jsx, jsxs, jsxDEV, Fragment} from "./jsx-runtime";
+export {delegateEvents, defineEvents, emit} from "./events";
+export type {Emit} from "./events";
+export {Show, For, createShow, createFor, insert, toChildren} from "./control";
+export {onMount, onCleanup, createLifecycle, render, connect, disconnect} from "./lifecycle";
+export {useFormValue, useInternals, onFormReset, onFormStateRestore, resetForm, restoreForm} from "./form";
+export {jsx, jsxs, jsxDEV, Fragment, createComponent, setAttr} from "./jsx-runtime";
 
 /**
  * Helper used **inside** compiled custom elements to convert an incoming
@@ -14,3 +19,43 @@ export {jsx, jsxs, jsxDEV, Fragment} from "./jsx-runtime";
 export function useProp<T>(props: Record<string, any>, key: string, fallback: T): Signal<T> {
   return signal((props[key] as T) ?? fallback);
 }
+
+/**
+ * Marks a component to be compiled into its own custom element, optionally
+ * under an explicit tag name. The compiler removes the call; uncompiled code
+ * gets the component back unchanged.
+ * @param tagOrComponent tag name, or the component itself
+ * @param component the component when a tag name is given
+ * @returns the component
+ */
+export function defineElement<C extends (props: any) => unknown>(component: C): C;
+export function defineElement<C extends (props: any) => unknown>(tag: string, component: C): C;
+export function defineElement(tagOrComponent: unknown, component?: unknown): unknown {
+  return component ?? tagOrComponent;
+}
+
+/**
+ * Tags the styles of the components of a module. The compiler adopts
+ * module-level `css` templates into the shadow root of every element the
+ * module defines; uncompiled code gets the CSS text.
+ * @param strings template strings
+ * @param values interpolated values
+ * @returns the CSS text
+ */
+export function css(strings: TemplateStringsArray, ...values: unknown[]): string {
+  return String.raw(strings, ...values);
+}
+
+/**
+ * Projects the children of the element, or those assigned to the slot
+ * `name`. The compiler lowers it to a `<slot>` element; uncompiled code gets
+ * one created at runtime.
+ * @param props slot name and fallback content
+ * @returns the slot element
+ */
+export function Slot(props: {name?: string; children?: Node | Node[]}): Node {
+  const slot = document.createElement("slot");
+  if (props.name) slot.name = props.name;
+  [props.children ?? []].flat().forEach((child) => slot.appendChild(child));
+  return slot;
+}
@@ -1,6 +1,7 @@
 // The automatic JSX runtime functions required by React 17+/SWC / Babel
 // "jsxImportSource: 'fluxel-core'" will import these.
 import {Signal, effect, isSignal} from "./signals";
+import {eventName} from "./events";
 
 /** Marker for JSX Fragments (`<>...</>`). */
 export const Fragment = Symbol("Fluxel.Fragment");
@@ -56,17 +57,47 @@ function appendChildren(parent: Node, child: any): void {
 function setProp(el: HTMLElement | SVGElement, key: string, value: any): void {
   if (key === "className") key = "class";
   if (key.startsWith("on") && typeof value === "function") {
-    const evt = key.slice(2).toLowerCase();
-    el.addEventListener(evt, value as EventListener);
-  } else if (value === false || value == null) {
-    el.removeAttribute(key);
-  } else if (value === true) {
-    el.setAttribute(key, "");
+    el.addEventListener(eventName(key), value as EventListener);
   } else {
-    el.setAttribute(key, String(value));
+    setAttr(el, key, value);
   }
 }
 
+/**
+ * Sets the attribute `name` of `el` to a JSX value: `null`, `undefined` and
+ * `false` remove it, `true` sets it empty.
+ * @param el element to set
+ * @param name attribute name
+ * @param value attribute value
+ */
+export function setAttr(el: Element, name: string, value: unknown): void {
+  if (value == null || value === false) el.removeAttribute(name);
+  else el.setAttribute(name, value === true ? "" : String(value));
+}
+
+/**
+ * Renders the component `type` with `props`. Modules compiled by the plugin
+ * export their components as custom element classes: those are created and
+ * given the props as properties, signals kept in sync, `onX` handlers as
+ * event listeners and the children in their light DOM. Functions are called.
+ * @param type component function or element class
+ * @param props component props
+ * @returns the rendered node
+ */
+export function createComponent(type: any, props: Props): Node {
+  if (!(type.prototype instanceof HTMLElement)) return type(props);
+
+  const el: HTMLElement & Record<string, any> = new type();
+  Object.keys(props).forEach((k) => {
+    const v = props[k];
+    if (k === "children") appendChildren(el, v);
+    else if (k.startsWith("on") && typeof v === "function") el.addEventListener(eventName(k), v);
+    else if (isSignal(v)) effect(() => (el[k] = v.value));
+    else el[k] = v;
+  });
+  return el;
+}
+
 /*---------------------------------------------------------------
   The automatic JSX runtime exports (React 17+, SWC, Babel `automatic`)
  ---------------------------------------------------------------*/
@@ -97,9 +128,9 @@ export const jsxDEV = jsx as typeof jsx;
  * @internal
  */
 function _jsx(type: any, props: Props = {}): Node {
-  // Functional component
+  // Functional component or element class
   if (typeof type === "function") {
-    return type(props);
+    return createComponent(type, props);
   }
 
   // Fragment
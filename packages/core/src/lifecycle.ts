@@ -0,0 +1,127 @@
+// Lifecycle of compiled elements. The compiler renders a component through
+// `render`, so the hooks it registers and the effects it creates belong to the
+// element, and calls `connect` / `disconnect` from the element's
+// `connectedCallback` / `disconnectedCallback`. The hooks only have an effect
+// at the top level of a compiled component.
+import {owned} from "./signals";
+
+/** Runs when the element is connected; may return a function run on disconnect. */
+export type MountFn = () => void | (() => void);
+
+/** Hooks and effects of one element instance. */
+export interface Lifecycle {
+  mounts: MountFn[];
+  cleanups: Array<() => void>;
+  /** disposers of the effects created by the render */
+  effects: Array<() => void>;
+  /** cleanups returned by the mount hooks of the current connection */
+  unmounts: Array<() => void>;
+  connected: boolean;
+  /** internals of a form-associated element */
+  internals?: ElementInternals;
+  formResets: Array<() => void>;
+  formRestores: Array<(state: unknown, mode: string) => void>;
+}
+
+/** Lifecycle of the element whose component is rendering. */
+let current: Lifecycle | null = null;
+
+/**
+ * @param internals internals of a form-associated element
+ * @returns the lifecycle of a new element
+ */
+export function createLifecycle(internals?: ElementInternals): Lifecycle {
+  return {
+    mounts: [],
+    cleanups: [],
+    effects: [],
+    unmounts: [],
+    connected: false,
+    internals,
+    formResets: [],
+    formRestores: [],
+  };
+}
+
+/**
+ * @returns the lifecycle of the element whose component is rendering
+ * @internal
+ */
+export function currentLifecycle(): Lifecycle | null {
+  return current;
+}
+
+/**
+ * Renders a component for the element owning `lifecycle`.
+ * @param lifecycle lifecycle of the element
+ * @param fn calls the component
+ * @returns the rendered node
+ */
+export function render<T>(lifecycle: Lifecycle, fn: () => T): T {
+  const prev = current;
+  current = lifecycle;
+  try {
+    return owned(lifecycle.effects, fn);
+  } finally {
+    current = prev;
+  }
+}
+
+/**
+ * Runs `fn` each time the element is connected to a document. A returned
+ * function runs when it is disconnected again.
+ * @param fn mount hook
+ */
+export function onMount(fn: MountFn): void {
+  current?.mounts.push(fn);
+}
+
+/**
+ * Runs `fn` when the element is removed from the document.
+ * @param fn cleanup hook
+ */
+export function onCleanup(fn: () => void): void {
+  current?.cleanups.push(fn);
+}
+
+/**
+ * Runs the mount hooks. Called by `connectedCallback`.
+ * @param lifecycle lifecycle of the element
+ */
+export function connect(lifecycle: Lifecycle): void {
+  // reconnected before the pending disconnect ran: the element only moved
+  if (lifecycle.connected) return;
+  lifecycle.connected = true;
+  for (const mount of lifecycle.mounts) {
+    const unmount = mount();
+    if (typeof unmount === "function") lifecycle.unmounts.push(unmount);
+  }
+}
+
+/**
+ * Runs the cleanups and disposes the effects of the element once it has
+ * left the document. Called by `disconnectedCallback`; moving an element
+ * disconnects and reconnects it within the same task, which is not a
+ * removal and keeps the state of its component. An element still detached
+ * when the microtask runs is removed: `reset` drops its disposed render and
+ * the hooks it registered are forgotten, so that the next connection renders
+ * again from scratch.
+ * @param lifecycle lifecycle of the element
+ * @param host the element
+ * @param reset discards the rendered nodes
+ */
+export function disconnect(lifecycle: Lifecycle, host: Node, reset?: () => void): void {
+  queueMicrotask(() => {
+    if (host.isConnected || !lifecycle.connected) return;
+    lifecycle.connected = false;
+    lifecycle.unmounts.splice(0).forEach((unmount) => unmount());
+    lifecycle.cleanups.forEach((cleanup) => cleanup());
+    lifecycle.effects.splice(0).forEach((dispose) => dispose());
+    if (!reset) return;
+    lifecycle.mounts.length = 0;
+    lifecycle.cleanups.length = 0;
+    lifecycle.formResets.length = 0;
+    lifecycle.formRestores.length = 0;
+    reset();
+  });
+}
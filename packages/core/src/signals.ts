@@ -22,6 +22,9 @@ let active: EffectFn | null = null;
 /** Stack of nested effects (supports effect inside effect). */
 const stack: EffectFn[] = [];
 
+/** Collects the disposers of the effects created while it is set. */
+let owner: Array<() => void> | null = null;
+
 /** Flag and queue used for micro‑task batching. */
 let queued = false;
 const pending = new Set<EffectFn>();
@@ -54,7 +57,10 @@ function schedule(effects: Set<EffectFn>): void {
  * @param fn effect function to remove
  */
 function cleanup(fn: EffectFn): void {
-  deps.forEach((set) => set.delete(fn));
+  deps.forEach((set, sig) => {
+    set.delete(fn);
+    if (!set.size) deps.delete(sig);
+  });
 }
 
 /*-------------------------------------------------------------------
@@ -94,8 +100,9 @@ export function signal<T>(initial: T): Signal<T> {
 
 /**
  * Run a function once and again whenever **any** of the signals it reads change.
- * Returns nothing. Effects are automatically batched.
+ * Effects are automatically batched.
  * @param fn function to run
+ * @returns a function that stops the effect
  * @example
  *  const count = signal(0);
  *  count(1); // triggers subscribers
@@ -105,19 +112,60 @@ export function signal<T>(initial: T): Signal<T> {
  *    console.log(count.value);
  *  });
  */
-export function effect(fn: EffectFn): void {
+export function effect(fn: EffectFn): () => void {
+  // effects created by later runs belong to the same owner
+  const parent = owner;
+  let disposed = false;
   const runner: EffectFn = () => {
+    if (disposed) return;
     cleanup(runner);
     stack.push(runner);
     active = runner;
+    const prev = owner;
+    owner = parent;
     try {
       fn();
     } finally {
+      owner = prev;
       stack.pop();
       active = stack[stack.length - 1] || null;
     }
   };
+  const dispose = () => {
+    disposed = true;
+    cleanup(runner);
+    pending.delete(runner);
+  };
+  parent?.push(dispose);
   runner();
+  return dispose;
+}
+
+/**
+ * Run `fn`, collecting a disposer for every effect it creates, including the
+ * ones later created by those effects.
+ * @param disposers list the disposers are added to
+ * @param fn function to run
+ * @returns return value of the function
+ */
+export function owned<T>(disposers: Array<() => void>, fn: () => T): T {
+  const prev = owner;
+  owner = disposers;
+  try {
+    return fn();
+  } finally {
+    owner = prev;
+  }
+}
+
+/**
+ * Runs `fn` when the effects of the current owner are disposed, e.g. to
+ * dispose the effects of a scope nested in it.
+ * @param fn function to run
+ * @internal
+ */
+export function onDispose(fn: () => void): void {
+  owner?.push(fn);
 }
 
 /**
@@ -0,0 +1,133 @@
+import {describe, expect, it} from "vitest";
+import {createFor, createShow, insert, toChildren} from "../src/control";
+import {effect, signal} from "../src/signals";
+
+/** Lets the effects of changed signals run. */
+const flush = () => new Promise<void>((resolve) => setTimeout(resolve));
+
+describe("toChildren", () => {
+  it("skips values rendering nothing and flattens arrays", () => {
+    expect(toChildren([null, "a", [1, false, [undefined, "b"]], true])).toEqual(["a", "1", "b"]);
+    expect(toChildren(false)).toEqual([]);
+  });
+
+  it("keeps nodes", () => {
+    const node = document.createElement("b");
+    expect(toChildren(node)[0]).toBe(node);
+  });
+});
+
+describe("insert", () => {
+  it("swaps text and nodes when the signals change", async () => {
+    const n = signal(0);
+    const parent = document.createElement("p");
+    parent.append(insert(() => (n.value > 0 ? document.createElement("i") : "zero")));
+    expect(parent.textContent).toBe("zero");
+
+    n.value = 1;
+    await flush();
+    expect(parent.childNodes[0]).toBeInstanceOf(Element);
+
+    n.value = -1;
+    await flush();
+    expect(parent.textContent).toBe("zero");
+  });
+
+  it("disposes the effects of the content it replaces", async () => {
+    const n = signal(1);
+    const label = signal("a");
+    let runs = 0;
+    insert(() => {
+      if (n.value === 0) return "none";
+      const b = document.createElement("b");
+      effect(() => void (runs++, (b.textContent = label.value)));
+      return b;
+    });
+    n.value = 0;
+    await flush();
+    label.value = "b";
+    await flush();
+    expect(runs).toBe(1);
+  });
+
+  it("updates text in place and renders nothing for null", async () => {
+    const label = signal<string | null>("a");
+    const parent = document.createElement("p");
+    parent.append(insert(() => label.value));
+    const text = parent.childNodes[0];
+
+    label.value = "b";
+    await flush();
+    expect(parent.childNodes[0]).toBe(text);
+    expect(parent.textContent).toBe("b");
+
+    label.value = null;
+    await flush();
+    expect(parent.textContent).toBe("");
+  });
+});
+
+describe("createShow", () => {
+  it("disposes the effects of the branch it hides", async () => {
+    const open = signal(true);
+    const label = signal("a");
+    const runs: string[] = [];
+    const parent = document.createElement("div");
+    const branch = () => {
+      const p = document.createElement("p");
+      effect(() => void (runs.push(label.value), (p.textContent = label.value)));
+      return p;
+    };
+    parent.append(createShow(() => open.value, branch));
+
+    open.value = false;
+    await flush();
+    label.value = "b";
+    await flush();
+    expect(runs).toEqual(["a"]);
+
+    open.value = true;
+    await flush();
+    expect(parent.textContent).toBe("b");
+  });
+});
+
+describe("createFor", () => {
+  it("disposes the effects of removed rows", async () => {
+    const items = signal(["a", "b"]);
+    const suffix = signal("!");
+    const runs: string[] = [];
+    const parent = document.createElement("ul");
+    const row = (item: string) => {
+      const li = document.createElement("li");
+      effect(() => void (runs.push(item), (li.textContent = item + suffix.value)));
+      return li;
+    };
+    parent.append(createFor(() => items.value, row));
+
+    items.value = ["b"];
+    await flush();
+    suffix.value = "?";
+    await flush();
+    expect(runs).toEqual(["a", "b", "b"]);
+    expect(parent.textContent).toBe("b?");
+  });
+
+  it("renders a row again for an updated copy of its item", async () => {
+    type Todo = {id: number; title: string};
+    const items = signal<Todo[]>([{id: 1, title: "Milk"}, {id: 2, title: "Eggs"}]);
+    const parent = document.createElement("ul");
+    const row = (todo: Todo) => {
+      const li = document.createElement("li");
+      li.textContent = todo.title;
+      return li;
+    };
+    parent.append(createFor(() => items.value, row, (todo) => todo.id));
+    const eggs = parent.childNodes[1];
+
+    items.value = items.value.map((todo) => (todo.id === 1 ? {...todo, title: "Oat milk"} : todo));
+    await flush();
+    expect(parent.textContent).toBe("Oat milkEggs");
+    expect(parent.childNodes[1]).toBe(eggs);
+  });
+});
@@ -0,0 +1,22 @@
+import {describe, expect, it} from "vitest";
+import {delegateEvents} from "../src/events";
+
+describe("delegateEvents", () => {
+  it("calls the handlers of nested shadow roots once", () => {
+    // <fluxel-outer> renders <fluxel-inner>, which renders a button
+    const outer = document.createElement("fluxel-outer").attachShadow({mode: "open"});
+    const host = document.createElement("fluxel-inner");
+    outer.append(host);
+    const inner = host.attachShadow({mode: "open"});
+    const button = document.createElement("button");
+    inner.append(button);
+    delegateEvents(outer, ["click"]);
+    delegateEvents(inner, ["click"]);
+
+    const clicks: string[] = [];
+    Object.assign(button, {$$click: () => clicks.push("button")});
+    Object.assign(host, {$$click: () => clicks.push("host")});
+    button.dispatchEvent(new Event("click", {bubbles: true, composed: true}));
+    expect(clicks).toEqual(["button", "host"]);
+  });
+});
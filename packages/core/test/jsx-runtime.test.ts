@@ -0,0 +1,58 @@
+import {describe, expect, it} from "vitest";
+import {createComponent, setAttr} from "../src/jsx-runtime";
+import {signal} from "../src/signals";
+
+/** Lets the effects of changed signals run. */
+const flush = () => new Promise<void>((resolve) => setTimeout(resolve));
+
+/** Element class as a compiled module exports it. */
+class StarIcon extends HTMLElement {
+  size: unknown;
+}
+customElements.define("fluxel-star-icon", StarIcon);
+
+describe("createComponent", () => {
+  it("creates imported element classes", async () => {
+    const size = signal(1);
+    let resets = 0;
+    const el = createComponent(StarIcon, {size, onReset: () => resets++, children: "Star"});
+
+    expect(el).toBeInstanceOf(StarIcon);
+    expect((el as StarIcon).size).toBe(1);
+    expect(el.textContent).toBe("Star");
+    size.value = 2;
+    await flush();
+    expect((el as StarIcon).size).toBe(2);
+    el.dispatchEvent(new Event("reset"));
+    expect(resets).toBe(1);
+  });
+
+  it("calls component functions", () => {
+    const Label = (props: {text: string}) => {
+      const b = document.createElement("b");
+      b.textContent = props.text;
+      return b;
+    };
+    expect(createComponent(Label, {text: "x"}).textContent).toBe("x");
+  });
+});
+
+describe("setAttr", () => {
+  it("removes the attribute for undefined and false", () => {
+    const el = document.createElement("span");
+    el.setAttribute("title", "Close");
+    setAttr(el, "title", undefined);
+    expect(el.hasAttribute("title")).toBe(false);
+    el.setAttribute("aria-hidden", "true");
+    setAttr(el, "aria-hidden", false);
+    expect(el.hasAttribute("aria-hidden")).toBe(false);
+  });
+
+  it("sets true as an empty attribute and other values as text", () => {
+    const el = document.createElement("span");
+    setAttr(el, "data-on", true);
+    expect(el.getAttribute("data-on")).toBe("");
+    setAttr(el, "data-count", 0);
+    expect(el.getAttribute("data-count")).toBe("0");
+  });
+});
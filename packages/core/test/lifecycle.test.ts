@@ -0,0 +1,73 @@
+import {describe, expect, it} from "vitest";
+import {connect, createLifecycle, disconnect, onMount, render} from "../src/lifecycle";
+import {effect, signal} from "../src/signals";
+
+/** Lets the pending `disconnect` and effect runs happen. */
+const flush = () => new Promise<void>((resolve) => setTimeout(resolve));
+
+/**
+ * Element the way the compiler builds it: renders when connected unless it
+ * has already, and resets through `disconnect`.
+ */
+function element(count: {value: number}) {
+  const lifecycle = createLifecycle();
+  const host = {isConnected: false, text: "", rendered: false, mounts: 0, clicks: signal(0)};
+  const connected = () => {
+    host.isConnected = true;
+    if (!host.rendered) {
+      host.rendered = true;
+      render(lifecycle, () => {
+        // state local to the component
+        host.clicks = signal(0);
+        onMount(() => void host.mounts++);
+        effect(() => void (host.text = String(count.value)));
+      });
+    }
+    connect(lifecycle);
+  };
+  const disconnected = () => {
+    host.isConnected = false;
+    disconnect(lifecycle, host as unknown as Node, () => {
+      host.rendered = false;
+      host.text = "";
+    });
+  };
+  return {lifecycle, host, connected, disconnected};
+}
+
+describe("disconnect", () => {
+  it("renders again when the element is reconnected", async () => {
+    const count = signal(1);
+    const {lifecycle, host, connected, disconnected} = element(count);
+    connected();
+    host.clicks.value = 3;
+    disconnected();
+    await flush();
+    expect(host.rendered).toBe(false);
+    expect(lifecycle.effects).toHaveLength(0);
+
+    connected();
+    expect(host.text).toBe("1");
+    expect(host.clicks.value).toBe(0);
+    count.value = 2;
+    await flush();
+    expect(host.text).toBe("2");
+    // the hooks of the first render are not run again
+    expect(lifecycle.mounts).toHaveLength(1);
+    expect(host.mounts).toBe(2);
+  });
+
+  it("keeps the render and state of a moved element", async () => {
+    const count = signal(1);
+    const {lifecycle, host, connected, disconnected} = element(count);
+    connected();
+    host.clicks.value = 3;
+    disconnected();
+    connected();
+    await flush();
+    expect(host.rendered).toBe(true);
+    expect(host.clicks.value).toBe(3);
+    expect(lifecycle.effects).toHaveLength(1);
+    expect(host.mounts).toBe(1);
+  });
+});
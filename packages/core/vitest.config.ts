@@ -0,0 +1,7 @@
+import {defineConfig} from "vitest/config";
+
+export default defineConfig({
+  test: {
+    environment: "happy-dom",
+  },
+});